@@ -79,6 +79,55 @@ func (EncryptedType) EnumDescriptor() ([]byte, []int) {
 	return file_oracle_oracle_proto_rawDescGZIP(), []int{0}
 }
 
+type AggregateOp int32
+
+const (
+	AggregateOp_Sum        AggregateOp = 0
+	AggregateOp_CountAbove AggregateOp = 1
+	AggregateOp_Mean       AggregateOp = 2
+)
+
+// Enum value maps for AggregateOp.
+var (
+	AggregateOp_name = map[int32]string{
+		0: "Sum",
+		1: "CountAbove",
+		2: "Mean",
+	}
+	AggregateOp_value = map[string]int32{
+		"Sum":        0,
+		"CountAbove": 1,
+		"Mean":       2,
+	}
+)
+
+func (x AggregateOp) Enum() *AggregateOp {
+	p := new(AggregateOp)
+	*p = x
+	return p
+}
+
+func (x AggregateOp) String() string {
+	return protoimpl.X.EnumStringOf(x.Descriptor(), protoreflect.EnumNumber(x))
+}
+
+func (AggregateOp) Descriptor() protoreflect.EnumDescriptor {
+	return file_oracle_oracle_proto_enumTypes[1].Descriptor()
+}
+
+func (AggregateOp) Type() protoreflect.EnumType {
+	return &file_oracle_oracle_proto_enumTypes[1]
+}
+
+func (x AggregateOp) Number() protoreflect.EnumNumber {
+	return protoreflect.EnumNumber(x)
+}
+
+// Deprecated: Use AggregateOp.Descriptor instead.
+func (AggregateOp) EnumDescriptor() ([]byte, []int) {
+	return file_oracle_oracle_proto_rawDescGZIP(), []int{1}
+}
+
 type FheEncrypted struct {
 	state         protoimpl.MessageState
 	sizeCache     protoimpl.SizeCache
@@ -483,6 +532,272 @@ func (x *ReencryptResponse) GetSignature() string {
 	return ""
 }
 
+// The request message containing the encrypted values to aggregate
+// homomorphically, the aggregate to compute and the decimal threshold used by
+// CountAbove, plus some proof (for future use).
+//
+// All values must share one EncryptedType. Sum wraps modulo 2^n for that
+// n-bit type, as homomorphic addition does. Mean adds the values in
+// n + ceil(log2(count)) bits, so the sum cannot wrap, and divides by the
+// number of values, rounding down.
+//
+// Oracles refuse fewer values than their configured minimum, which is never
+// below 2, and the same ciphertext given twice. These checks only reject
+// malformed input and do not protect individual values: padding a set with
+// known values, differencing overlapping sets or bisecting a CountAbove
+// threshold still reveals any one of them. Oracles that must protect
+// individual values charge every input against a per-input release budget.
+type AggregateRequest struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Encrypted []*FheEncrypted `protobuf:"bytes,1,rep,name=encrypted,proto3" json:"encrypted,omitempty"`
+	Op        AggregateOp     `protobuf:"varint,2,opt,name=op,proto3,enum=oracle.AggregateOp" json:"op,omitempty"`
+	Threshold string          `protobuf:"bytes,3,opt,name=threshold,proto3" json:"threshold,omitempty"`
+	Proof     string          `protobuf:"bytes,4,opt,name=proof,proto3" json:"proof,omitempty"`
+}
+
+func (x *AggregateRequest) Reset() {
+	*x = AggregateRequest{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_oracle_oracle_proto_msgTypes[7]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *AggregateRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*AggregateRequest) ProtoMessage() {}
+
+func (x *AggregateRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_oracle_oracle_proto_msgTypes[7]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use AggregateRequest.ProtoReflect.Descriptor instead.
+func (*AggregateRequest) Descriptor() ([]byte, []int) {
+	return file_oracle_oracle_proto_rawDescGZIP(), []int{7}
+}
+
+func (x *AggregateRequest) GetEncrypted() []*FheEncrypted {
+	if x != nil {
+		return x.Encrypted
+	}
+	return nil
+}
+
+func (x *AggregateRequest) GetOp() AggregateOp {
+	if x != nil {
+		return x.Op
+	}
+	return AggregateOp_Sum
+}
+
+func (x *AggregateRequest) GetThreshold() string {
+	if x != nil {
+		return x.Threshold
+	}
+	return ""
+}
+
+func (x *AggregateRequest) GetProof() string {
+	if x != nil {
+		return x.Proof
+	}
+	return ""
+}
+
+// The response message containing only the decrypted aggregate
+type AggregateResponse struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Aggregate string `protobuf:"bytes,1,opt,name=aggregate,proto3" json:"aggregate,omitempty"`
+	Signature string `protobuf:"bytes,2,opt,name=signature,proto3" json:"signature,omitempty"`
+}
+
+func (x *AggregateResponse) Reset() {
+	*x = AggregateResponse{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_oracle_oracle_proto_msgTypes[8]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *AggregateResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*AggregateResponse) ProtoMessage() {}
+
+func (x *AggregateResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_oracle_oracle_proto_msgTypes[8]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use AggregateResponse.ProtoReflect.Descriptor instead.
+func (*AggregateResponse) Descriptor() ([]byte, []int) {
+	return file_oracle_oracle_proto_rawDescGZIP(), []int{8}
+}
+
+func (x *AggregateResponse) GetAggregate() string {
+	if x != nil {
+		return x.Aggregate
+	}
+	return ""
+}
+
+func (x *AggregateResponse) GetSignature() string {
+	if x != nil {
+		return x.Signature
+	}
+	return ""
+}
+
+// The request message containing an encrypted value and the id of a bucket
+// set the oracle has configured for the value's key, plus some proof (for
+// future use). Callers cannot supply bounds of their own, so they cannot
+// narrow the buckets down to the value itself.
+type BucketRequest struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Encrypted *FheEncrypted `protobuf:"bytes,1,opt,name=encrypted,proto3" json:"encrypted,omitempty"`
+	BucketSet string        `protobuf:"bytes,2,opt,name=bucket_set,json=bucketSet,proto3" json:"bucket_set,omitempty"`
+	Proof     string        `protobuf:"bytes,3,opt,name=proof,proto3" json:"proof,omitempty"`
+}
+
+func (x *BucketRequest) Reset() {
+	*x = BucketRequest{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_oracle_oracle_proto_msgTypes[9]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *BucketRequest) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*BucketRequest) ProtoMessage() {}
+
+func (x *BucketRequest) ProtoReflect() protoreflect.Message {
+	mi := &file_oracle_oracle_proto_msgTypes[9]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use BucketRequest.ProtoReflect.Descriptor instead.
+func (*BucketRequest) Descriptor() ([]byte, []int) {
+	return file_oracle_oracle_proto_rawDescGZIP(), []int{9}
+}
+
+func (x *BucketRequest) GetEncrypted() *FheEncrypted {
+	if x != nil {
+		return x.Encrypted
+	}
+	return nil
+}
+
+func (x *BucketRequest) GetBucketSet() string {
+	if x != nil {
+		return x.BucketSet
+	}
+	return ""
+}
+
+func (x *BucketRequest) GetProof() string {
+	if x != nil {
+		return x.Proof
+	}
+	return ""
+}
+
+// The response message containing how many bounds of the bucket set the value
+// is at or above, so 0 means below the first bound, never the value itself
+type BucketResponse struct {
+	state         protoimpl.MessageState
+	sizeCache     protoimpl.SizeCache
+	unknownFields protoimpl.UnknownFields
+
+	Bucket    uint32 `protobuf:"varint,1,opt,name=bucket,proto3" json:"bucket,omitempty"`
+	Signature string `protobuf:"bytes,2,opt,name=signature,proto3" json:"signature,omitempty"`
+}
+
+func (x *BucketResponse) Reset() {
+	*x = BucketResponse{}
+	if protoimpl.UnsafeEnabled {
+		mi := &file_oracle_oracle_proto_msgTypes[10]
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		ms.StoreMessageInfo(mi)
+	}
+}
+
+func (x *BucketResponse) String() string {
+	return protoimpl.X.MessageStringOf(x)
+}
+
+func (*BucketResponse) ProtoMessage() {}
+
+func (x *BucketResponse) ProtoReflect() protoreflect.Message {
+	mi := &file_oracle_oracle_proto_msgTypes[10]
+	if protoimpl.UnsafeEnabled && x != nil {
+		ms := protoimpl.X.MessageStateOf(protoimpl.Pointer(x))
+		if ms.LoadMessageInfo() == nil {
+			ms.StoreMessageInfo(mi)
+		}
+		return ms
+	}
+	return mi.MessageOf(x)
+}
+
+// Deprecated: Use BucketResponse.ProtoReflect.Descriptor instead.
+func (*BucketResponse) Descriptor() ([]byte, []int) {
+	return file_oracle_oracle_proto_rawDescGZIP(), []int{10}
+}
+
+func (x *BucketResponse) GetBucket() uint32 {
+	if x != nil {
+		return x.Bucket
+	}
+	return 0
+}
+
+func (x *BucketResponse) GetSignature() string {
+	if x != nil {
+		return x.Signature
+	}
+	return ""
+}
+
 var File_oracle_oracle_proto protoreflect.FileDescriptor
 
 var file_oracle_oracle_proto_rawDesc = []byte{
@@ -530,25 +845,64 @@ var file_oracle_oracle_proto_rawDesc = []byte{
 	0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x0b, 0x72,
 	0x65, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x12, 0x1c, 0x0a, 0x09, 0x73, 0x69,
 	0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73,
-	0x69, 0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x2a, 0x58, 0x0a, 0x0d, 0x45, 0x6e, 0x63, 0x72,
-	0x79, 0x70, 0x74, 0x65, 0x64, 0x54, 0x79, 0x70, 0x65, 0x12, 0x09, 0x0a, 0x05, 0x55, 0x69, 0x6e,
-	0x74, 0x38, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x69, 0x6e, 0x74, 0x31, 0x36, 0x10, 0x01,
-	0x12, 0x0a, 0x0a, 0x06, 0x55, 0x69, 0x6e, 0x74, 0x33, 0x32, 0x10, 0x02, 0x12, 0x0a, 0x0a, 0x06,
-	0x55, 0x69, 0x6e, 0x74, 0x36, 0x34, 0x10, 0x03, 0x12, 0x0b, 0x0a, 0x07, 0x55, 0x69, 0x6e, 0x74,
-	0x31, 0x32, 0x38, 0x10, 0x04, 0x12, 0x0b, 0x0a, 0x07, 0x55, 0x69, 0x6e, 0x74, 0x32, 0x35, 0x36,
-	0x10, 0x05, 0x32, 0xd2, 0x01, 0x0a, 0x10, 0x44, 0x65, 0x63, 0x72, 0x79, 0x70, 0x74, 0x69, 0x6f,
-	0x6e, 0x4f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x12, 0x3c, 0x0a, 0x07, 0x44, 0x65, 0x63, 0x72, 0x79,
-	0x70, 0x74, 0x12, 0x16, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x44, 0x65, 0x63, 0x72,
-	0x79, 0x70, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x17, 0x2e, 0x6f, 0x72, 0x61,
-	0x63, 0x6c, 0x65, 0x2e, 0x44, 0x65, 0x63, 0x72, 0x79, 0x70, 0x74, 0x52, 0x65, 0x73, 0x70, 0x6f,
-	0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x42, 0x0a, 0x09, 0x52, 0x65, 0x65, 0x6e, 0x63, 0x72, 0x79,
-	0x70, 0x74, 0x12, 0x18, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x52, 0x65, 0x65, 0x6e,
-	0x63, 0x72, 0x79, 0x70, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x19, 0x2e, 0x6f,
-	0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x52, 0x65, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x52,
-	0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x3c, 0x0a, 0x0b, 0x41, 0x73, 0x73,
-	0x65, 0x72, 0x74, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x12, 0x14, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c,
-	0x65, 0x2e, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x15,
-	0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x52, 0x65, 0x73,
+	0x69, 0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x22, 0xa4, 0x01, 0x0a, 0x10, 0x41, 0x67, 0x67,
+	0x72, 0x65, 0x67, 0x61, 0x74, 0x65, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x12, 0x37, 0x0a,
+	0x09, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x18, 0x01, 0x20, 0x03, 0x28, 0x0b,
+	0x32, 0x14, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x46, 0x68, 0x65, 0x45, 0x6e, 0x63,
+	0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x42, 0x03, 0xe0, 0x41, 0x02, 0x52, 0x09, 0x65, 0x6e, 0x63,
+	0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x12, 0x23, 0x0a, 0x02, 0x6f, 0x70, 0x18, 0x02, 0x20, 0x01,
+	0x28, 0x0e, 0x32, 0x13, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x41, 0x67, 0x67, 0x72,
+	0x65, 0x67, 0x61, 0x74, 0x65, 0x4f, 0x70, 0x52, 0x02, 0x6f, 0x70, 0x12, 0x1c, 0x0a, 0x09, 0x74,
+	0x68, 0x72, 0x65, 0x73, 0x68, 0x6f, 0x6c, 0x64, 0x18, 0x03, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09,
+	0x74, 0x68, 0x72, 0x65, 0x73, 0x68, 0x6f, 0x6c, 0x64, 0x12, 0x14, 0x0a, 0x05, 0x70, 0x72, 0x6f,
+	0x6f, 0x66, 0x18, 0x04, 0x20, 0x01, 0x28, 0x09, 0x52, 0x05, 0x70, 0x72, 0x6f, 0x6f, 0x66, 0x22,
+	0x4f, 0x0a, 0x11, 0x41, 0x67, 0x67, 0x72, 0x65, 0x67, 0x61, 0x74, 0x65, 0x52, 0x65, 0x73, 0x70,
+	0x6f, 0x6e, 0x73, 0x65, 0x12, 0x1c, 0x0a, 0x09, 0x61, 0x67, 0x67, 0x72, 0x65, 0x67, 0x61, 0x74,
+	0x65, 0x18, 0x01, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x61, 0x67, 0x67, 0x72, 0x65, 0x67, 0x61,
+	0x74, 0x65, 0x12, 0x1c, 0x0a, 0x09, 0x73, 0x69, 0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x18,
+	0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x69, 0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65,
+	0x22, 0x7d, 0x0a, 0x0d, 0x42, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73,
+	0x74, 0x12, 0x37, 0x0a, 0x09, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x18, 0x01,
+	0x20, 0x01, 0x28, 0x0b, 0x32, 0x14, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x46, 0x68,
+	0x65, 0x45, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x42, 0x03, 0xe0, 0x41, 0x02, 0x52,
+	0x09, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x65, 0x64, 0x12, 0x1d, 0x0a, 0x0a, 0x62, 0x75,
+	0x63, 0x6b, 0x65, 0x74, 0x5f, 0x73, 0x65, 0x74, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09,
+	0x62, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x53, 0x65, 0x74, 0x12, 0x14, 0x0a, 0x05, 0x70, 0x72, 0x6f,
+	0x6f, 0x66, 0x18, 0x03, 0x20, 0x01, 0x28, 0x09, 0x52, 0x05, 0x70, 0x72, 0x6f, 0x6f, 0x66, 0x22,
+	0x46, 0x0a, 0x0e, 0x42, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73,
+	0x65, 0x12, 0x16, 0x0a, 0x06, 0x62, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x18, 0x01, 0x20, 0x01, 0x28,
+	0x0d, 0x52, 0x06, 0x62, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x12, 0x1c, 0x0a, 0x09, 0x73, 0x69, 0x67,
+	0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x18, 0x02, 0x20, 0x01, 0x28, 0x09, 0x52, 0x09, 0x73, 0x69,
+	0x67, 0x6e, 0x61, 0x74, 0x75, 0x72, 0x65, 0x2a, 0x58, 0x0a, 0x0d, 0x45, 0x6e, 0x63, 0x72, 0x79,
+	0x70, 0x74, 0x65, 0x64, 0x54, 0x79, 0x70, 0x65, 0x12, 0x09, 0x0a, 0x05, 0x55, 0x69, 0x6e, 0x74,
+	0x38, 0x10, 0x00, 0x12, 0x0a, 0x0a, 0x06, 0x55, 0x69, 0x6e, 0x74, 0x31, 0x36, 0x10, 0x01, 0x12,
+	0x0a, 0x0a, 0x06, 0x55, 0x69, 0x6e, 0x74, 0x33, 0x32, 0x10, 0x02, 0x12, 0x0a, 0x0a, 0x06, 0x55,
+	0x69, 0x6e, 0x74, 0x36, 0x34, 0x10, 0x03, 0x12, 0x0b, 0x0a, 0x07, 0x55, 0x69, 0x6e, 0x74, 0x31,
+	0x32, 0x38, 0x10, 0x04, 0x12, 0x0b, 0x0a, 0x07, 0x55, 0x69, 0x6e, 0x74, 0x32, 0x35, 0x36, 0x10,
+	0x05, 0x2a, 0x30, 0x0a, 0x0b, 0x41, 0x67, 0x67, 0x72, 0x65, 0x67, 0x61, 0x74, 0x65, 0x4f, 0x70,
+	0x12, 0x07, 0x0a, 0x03, 0x53, 0x75, 0x6d, 0x10, 0x00, 0x12, 0x0e, 0x0a, 0x0a, 0x43, 0x6f, 0x75,
+	0x6e, 0x74, 0x41, 0x62, 0x6f, 0x76, 0x65, 0x10, 0x01, 0x12, 0x08, 0x0a, 0x04, 0x4d, 0x65, 0x61,
+	0x6e, 0x10, 0x02, 0x32, 0xd1, 0x02, 0x0a, 0x10, 0x44, 0x65, 0x63, 0x72, 0x79, 0x70, 0x74, 0x69,
+	0x6f, 0x6e, 0x4f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x12, 0x3c, 0x0a, 0x07, 0x44, 0x65, 0x63, 0x72,
+	0x79, 0x70, 0x74, 0x12, 0x16, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x44, 0x65, 0x63,
+	0x72, 0x79, 0x70, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x17, 0x2e, 0x6f, 0x72,
+	0x61, 0x63, 0x6c, 0x65, 0x2e, 0x44, 0x65, 0x63, 0x72, 0x79, 0x70, 0x74, 0x52, 0x65, 0x73, 0x70,
+	0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x42, 0x0a, 0x09, 0x52, 0x65, 0x65, 0x6e, 0x63, 0x72,
+	0x79, 0x70, 0x74, 0x12, 0x18, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x52, 0x65, 0x65,
+	0x6e, 0x63, 0x72, 0x79, 0x70, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x19, 0x2e,
+	0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x52, 0x65, 0x65, 0x6e, 0x63, 0x72, 0x79, 0x70, 0x74,
+	0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x3c, 0x0a, 0x0b, 0x41, 0x73,
+	0x73, 0x65, 0x72, 0x74, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x12, 0x14, 0x2e, 0x6f, 0x72, 0x61, 0x63,
+	0x6c, 0x65, 0x2e, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a,
+	0x15, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x49, 0x73, 0x4e, 0x69, 0x6c, 0x52, 0x65,
+	0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x42, 0x0a, 0x09, 0x41, 0x67, 0x67, 0x72,
+	0x65, 0x67, 0x61, 0x74, 0x65, 0x12, 0x18, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x41,
+	0x67, 0x67, 0x72, 0x65, 0x67, 0x61, 0x74, 0x65, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a,
+	0x19, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x41, 0x67, 0x67, 0x72, 0x65, 0x67, 0x61,
+	0x74, 0x65, 0x52, 0x65, 0x73, 0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x12, 0x39, 0x0a, 0x06,
+	0x42, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x12, 0x15, 0x2e, 0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e,
+	0x42, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x52, 0x65, 0x71, 0x75, 0x65, 0x73, 0x74, 0x1a, 0x16, 0x2e,
+	0x6f, 0x72, 0x61, 0x63, 0x6c, 0x65, 0x2e, 0x42, 0x75, 0x63, 0x6b, 0x65, 0x74, 0x52, 0x65, 0x73,
 	0x70, 0x6f, 0x6e, 0x73, 0x65, 0x22, 0x00, 0x42, 0x0b, 0x5a, 0x09, 0x67, 0x6f, 0x2f, 0x6f, 0x72,
 	0x61, 0x63, 0x6c, 0x65, 0x62, 0x06, 0x70, 0x72, 0x6f, 0x74, 0x6f, 0x33,
 }
@@ -565,34 +919,46 @@ func file_oracle_oracle_proto_rawDescGZIP() []byte {
 	return file_oracle_oracle_proto_rawDescData
 }
 
-var file_oracle_oracle_proto_enumTypes = make([]protoimpl.EnumInfo, 1)
-var file_oracle_oracle_proto_msgTypes = make([]protoimpl.MessageInfo, 7)
+var file_oracle_oracle_proto_enumTypes = make([]protoimpl.EnumInfo, 2)
+var file_oracle_oracle_proto_msgTypes = make([]protoimpl.MessageInfo, 11)
 var file_oracle_oracle_proto_goTypes = []interface{}{
 	(EncryptedType)(0),        // 0: oracle.EncryptedType
-	(*FheEncrypted)(nil),      // 1: oracle.FheEncrypted
-	(*IsNilRequest)(nil),      // 2: oracle.IsNilRequest
-	(*ReencryptRequest)(nil),  // 3: oracle.ReencryptRequest
-	(*DecryptRequest)(nil),    // 4: oracle.DecryptRequest
-	(*DecryptResponse)(nil),   // 5: oracle.DecryptResponse
-	(*IsNilResponse)(nil),     // 6: oracle.IsNilResponse
-	(*ReencryptResponse)(nil), // 7: oracle.ReencryptResponse
+	(AggregateOp)(0),          // 1: oracle.AggregateOp
+	(*FheEncrypted)(nil),      // 2: oracle.FheEncrypted
+	(*IsNilRequest)(nil),      // 3: oracle.IsNilRequest
+	(*ReencryptRequest)(nil),  // 4: oracle.ReencryptRequest
+	(*DecryptRequest)(nil),    // 5: oracle.DecryptRequest
+	(*DecryptResponse)(nil),   // 6: oracle.DecryptResponse
+	(*IsNilResponse)(nil),     // 7: oracle.IsNilResponse
+	(*ReencryptResponse)(nil), // 8: oracle.ReencryptResponse
+	(*AggregateRequest)(nil),  // 9: oracle.AggregateRequest
+	(*AggregateResponse)(nil), // 10: oracle.AggregateResponse
+	(*BucketRequest)(nil),     // 11: oracle.BucketRequest
+	(*BucketResponse)(nil),    // 12: oracle.BucketResponse
 }
 var file_oracle_oracle_proto_depIdxs = []int32{
-	0, // 0: oracle.FheEncrypted.type:type_name -> oracle.EncryptedType
-	1, // 1: oracle.IsNilRequest.encrypted:type_name -> oracle.FheEncrypted
-	1, // 2: oracle.ReencryptRequest.encrypted:type_name -> oracle.FheEncrypted
-	1, // 3: oracle.DecryptRequest.encrypted:type_name -> oracle.FheEncrypted
-	4, // 4: oracle.DecryptionOracle.Decrypt:input_type -> oracle.DecryptRequest
-	3, // 5: oracle.DecryptionOracle.Reencrypt:input_type -> oracle.ReencryptRequest
-	2, // 6: oracle.DecryptionOracle.AssertIsNil:input_type -> oracle.IsNilRequest
-	5, // 7: oracle.DecryptionOracle.Decrypt:output_type -> oracle.DecryptResponse
-	7, // 8: oracle.DecryptionOracle.Reencrypt:output_type -> oracle.ReencryptResponse
-	6, // 9: oracle.DecryptionOracle.AssertIsNil:output_type -> oracle.IsNilResponse
-	7, // [7:10] is the sub-list for method output_type
-	4, // [4:7] is the sub-list for method input_type
-	4, // [4:4] is the sub-list for extension type_name
-	4, // [4:4] is the sub-list for extension extendee
-	0, // [0:4] is the sub-list for field type_name
+	0,  // 0: oracle.FheEncrypted.type:type_name -> oracle.EncryptedType
+	2,  // 1: oracle.IsNilRequest.encrypted:type_name -> oracle.FheEncrypted
+	2,  // 2: oracle.ReencryptRequest.encrypted:type_name -> oracle.FheEncrypted
+	2,  // 3: oracle.DecryptRequest.encrypted:type_name -> oracle.FheEncrypted
+	2,  // 4: oracle.AggregateRequest.encrypted:type_name -> oracle.FheEncrypted
+	1,  // 5: oracle.AggregateRequest.op:type_name -> oracle.AggregateOp
+	2,  // 6: oracle.BucketRequest.encrypted:type_name -> oracle.FheEncrypted
+	5,  // 7: oracle.DecryptionOracle.Decrypt:input_type -> oracle.DecryptRequest
+	4,  // 8: oracle.DecryptionOracle.Reencrypt:input_type -> oracle.ReencryptRequest
+	3,  // 9: oracle.DecryptionOracle.AssertIsNil:input_type -> oracle.IsNilRequest
+	9,  // 10: oracle.DecryptionOracle.Aggregate:input_type -> oracle.AggregateRequest
+	11, // 11: oracle.DecryptionOracle.Bucket:input_type -> oracle.BucketRequest
+	6,  // 12: oracle.DecryptionOracle.Decrypt:output_type -> oracle.DecryptResponse
+	8,  // 13: oracle.DecryptionOracle.Reencrypt:output_type -> oracle.ReencryptResponse
+	7,  // 14: oracle.DecryptionOracle.AssertIsNil:output_type -> oracle.IsNilResponse
+	10, // 15: oracle.DecryptionOracle.Aggregate:output_type -> oracle.AggregateResponse
+	12, // 16: oracle.DecryptionOracle.Bucket:output_type -> oracle.BucketResponse
+	12, // [12:17] is the sub-list for method output_type
+	7,  // [7:12] is the sub-list for method input_type
+	7,  // [7:7] is the sub-list for extension type_name
+	7,  // [7:7] is the sub-list for extension extendee
+	0,  // [0:7] is the sub-list for field type_name
 }
 
 func init() { file_oracle_oracle_proto_init() }
@@ -685,14 +1051,62 @@ func file_oracle_oracle_proto_init() {
 				return nil
 			}
 		}
+		file_oracle_oracle_proto_msgTypes[7].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*AggregateRequest); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_oracle_oracle_proto_msgTypes[8].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*AggregateResponse); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_oracle_oracle_proto_msgTypes[9].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*BucketRequest); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
+		file_oracle_oracle_proto_msgTypes[10].Exporter = func(v interface{}, i int) interface{} {
+			switch v := v.(*BucketResponse); i {
+			case 0:
+				return &v.state
+			case 1:
+				return &v.sizeCache
+			case 2:
+				return &v.unknownFields
+			default:
+				return nil
+			}
+		}
 	}
 	type x struct{}
 	out := protoimpl.TypeBuilder{
 		File: protoimpl.DescBuilder{
 			GoPackagePath: reflect.TypeOf(x{}).PkgPath(),
 			RawDescriptor: file_oracle_oracle_proto_rawDesc,
-			NumEnums:      1,
-			NumMessages:   7,
+			NumEnums:      2,
+			NumMessages:   11,
 			NumExtensions: 0,
 			NumServices:   1,
 		},
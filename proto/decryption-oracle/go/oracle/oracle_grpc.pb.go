@@ -22,6 +22,8 @@ const (
 	DecryptionOracle_Decrypt_FullMethodName     = "/oracle.DecryptionOracle/Decrypt"
 	DecryptionOracle_Reencrypt_FullMethodName   = "/oracle.DecryptionOracle/Reencrypt"
 	DecryptionOracle_AssertIsNil_FullMethodName = "/oracle.DecryptionOracle/AssertIsNil"
+	DecryptionOracle_Aggregate_FullMethodName   = "/oracle.DecryptionOracle/Aggregate"
+	DecryptionOracle_Bucket_FullMethodName      = "/oracle.DecryptionOracle/Bucket"
 )
 
 // DecryptionOracleClient is the client API for DecryptionOracle service.
@@ -32,6 +34,8 @@ type DecryptionOracleClient interface {
 	Decrypt(ctx context.Context, in *DecryptRequest, opts ...grpc.CallOption) (*DecryptResponse, error)
 	Reencrypt(ctx context.Context, in *ReencryptRequest, opts ...grpc.CallOption) (*ReencryptResponse, error)
 	AssertIsNil(ctx context.Context, in *IsNilRequest, opts ...grpc.CallOption) (*IsNilResponse, error)
+	Aggregate(ctx context.Context, in *AggregateRequest, opts ...grpc.CallOption) (*AggregateResponse, error)
+	Bucket(ctx context.Context, in *BucketRequest, opts ...grpc.CallOption) (*BucketResponse, error)
 }
 
 type decryptionOracleClient struct {
@@ -69,6 +73,24 @@ func (c *decryptionOracleClient) AssertIsNil(ctx context.Context, in *IsNilReque
 	return out, nil
 }
 
+func (c *decryptionOracleClient) Aggregate(ctx context.Context, in *AggregateRequest, opts ...grpc.CallOption) (*AggregateResponse, error) {
+	out := new(AggregateResponse)
+	err := c.cc.Invoke(ctx, DecryptionOracle_Aggregate_FullMethodName, in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
+func (c *decryptionOracleClient) Bucket(ctx context.Context, in *BucketRequest, opts ...grpc.CallOption) (*BucketResponse, error) {
+	out := new(BucketResponse)
+	err := c.cc.Invoke(ctx, DecryptionOracle_Bucket_FullMethodName, in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
 // DecryptionOracleServer is the server API for DecryptionOracle service.
 // All implementations must embed UnimplementedDecryptionOracleServer
 // for forward compatibility
@@ -77,6 +99,8 @@ type DecryptionOracleServer interface {
 	Decrypt(context.Context, *DecryptRequest) (*DecryptResponse, error)
 	Reencrypt(context.Context, *ReencryptRequest) (*ReencryptResponse, error)
 	AssertIsNil(context.Context, *IsNilRequest) (*IsNilResponse, error)
+	Aggregate(context.Context, *AggregateRequest) (*AggregateResponse, error)
+	Bucket(context.Context, *BucketRequest) (*BucketResponse, error)
 	mustEmbedUnimplementedDecryptionOracleServer()
 }
 
@@ -93,6 +117,12 @@ func (UnimplementedDecryptionOracleServer) Reencrypt(context.Context, *Reencrypt
 func (UnimplementedDecryptionOracleServer) AssertIsNil(context.Context, *IsNilRequest) (*IsNilResponse, error) {
 	return nil, status.Errorf(codes.Unimplemented, "method AssertIsNil not implemented")
 }
+func (UnimplementedDecryptionOracleServer) Aggregate(context.Context, *AggregateRequest) (*AggregateResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method Aggregate not implemented")
+}
+func (UnimplementedDecryptionOracleServer) Bucket(context.Context, *BucketRequest) (*BucketResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method Bucket not implemented")
+}
 func (UnimplementedDecryptionOracleServer) mustEmbedUnimplementedDecryptionOracleServer() {}
 
 // UnsafeDecryptionOracleServer may be embedded to opt out of forward compatibility for this service.
@@ -160,6 +190,42 @@ func _DecryptionOracle_AssertIsNil_Handler(srv interface{}, ctx context.Context,
 	return interceptor(ctx, in, info, handler)
 }
 
+func _DecryptionOracle_Aggregate_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(AggregateRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(DecryptionOracleServer).Aggregate(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: DecryptionOracle_Aggregate_FullMethodName,
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(DecryptionOracleServer).Aggregate(ctx, req.(*AggregateRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
+func _DecryptionOracle_Bucket_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(BucketRequest)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(DecryptionOracleServer).Bucket(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: DecryptionOracle_Bucket_FullMethodName,
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(DecryptionOracleServer).Bucket(ctx, req.(*BucketRequest))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
 // DecryptionOracle_ServiceDesc is the grpc.ServiceDesc for DecryptionOracle service.
 // It's only intended for direct use with grpc.RegisterService,
 // and not to be introspected or modified (even as a copy)
@@ -179,6 +245,14 @@ var DecryptionOracle_ServiceDesc = grpc.ServiceDesc{
 			MethodName: "AssertIsNil",
 			Handler:    _DecryptionOracle_AssertIsNil_Handler,
 		},
+		{
+			MethodName: "Aggregate",
+			Handler:    _DecryptionOracle_Aggregate_Handler,
+		},
+		{
+			MethodName: "Bucket",
+			Handler:    _DecryptionOracle_Bucket_Handler,
+		},
 	},
 	Streams:  []grpc.StreamDesc{},
 	Metadata: "oracle/oracle.proto",
@@ -1,6 +1,5 @@
 fn main() {
-    #[cfg(not(feature = "build_proto"))]
-    {
+    if cfg!(not(feature = "build_proto")) {
         return;
     }
 
@@ -8,6 +7,8 @@ fn main() {
     let out_dir = "./src/oracle";
     tonic_build::configure()
         .file_descriptor_set_path("oracle.bin")
+        .server_mod_attribute("oracle", "#[cfg(feature = \"server\")]")
+        .client_mod_attribute("oracle", "#[cfg(feature = \"client\")]")
         .out_dir(out_dir)
         .compile(&["oracle/oracle.proto"], &["../proto"])
         .unwrap();
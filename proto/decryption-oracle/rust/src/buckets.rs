@@ -0,0 +1,62 @@
+//! Server-side bucket sets for the Bucket RPC.
+//!
+//! A Bucket call only names a set; the bounds are configured here per key.
+//! Were callers free to choose bounds, a few calls bisecting the range would
+//! recover the exact plaintext.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use tonic::Status;
+
+/// Ascending lower bounds of a set of buckets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BucketSet {
+    bounds: Vec<u128>,
+}
+
+impl BucketSet {
+    /// Returns `None` unless `bounds` is non-empty and strictly ascending.
+    pub fn new(bounds: Vec<u128>) -> Option<Self> {
+        let ascending = bounds.windows(2).all(|pair| pair[0] < pair[1]);
+        (!bounds.is_empty() && ascending).then_some(Self { bounds })
+    }
+
+    /// The number of bounds `value` is at or above.
+    pub fn bucket(&self, value: u128) -> u32 {
+        self.bounds.partition_point(|bound| *bound <= value) as u32
+    }
+}
+
+/// Bucket sets keyed by key id and set id, replaceable while the server runs.
+#[derive(Debug, Default)]
+pub struct BucketSets {
+    sets: RwLock<HashMap<(String, String), Arc<BucketSet>>>,
+}
+
+impl BucketSets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, key_id: impl Into<String>, set_id: impl Into<String>, set: BucketSet) {
+        let id = (key_id.into(), set_id.into());
+        self.sets.write().unwrap().insert(id, Arc::new(set));
+    }
+
+    pub fn remove(&self, key_id: &str, set_id: &str) -> Option<Arc<BucketSet>> {
+        let id = (key_id.to_owned(), set_id.to_owned());
+        self.sets.write().unwrap().remove(&id)
+    }
+
+    /// Fails with `NOT_FOUND` unless `set_id` is configured for `key_id`.
+    pub fn get(&self, key_id: &str, set_id: &str) -> Result<Arc<BucketSet>, Status> {
+        let id = (key_id.to_owned(), set_id.to_owned());
+        self.sets
+            .read()
+            .unwrap()
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("no bucket set {set_id} for key {key_id}")))
+    }
+}
@@ -0,0 +1,189 @@
+//! Fault injection for resilience testing.
+//!
+//! [`Chaos`] wraps an oracle and injects the failures a deployment sees in
+//! practice: slow calls, responses lost after the work was done, calls that
+//! never answer, corrupted ciphertext bytes and windows during which the
+//! decryption key is unavailable. Point clients at a wrapped oracle to
+//! exercise their retry, quorum and signature checks. Only built with the
+//! `chaos` feature.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tonic::{Request, Response, Status};
+
+use crate::oracle::decryption_oracle_server::DecryptionOracle;
+use crate::oracle::{
+    AggregateRequest, AggregateResponse, BucketRequest, BucketResponse, DecryptRequest,
+    DecryptResponse, FheEncrypted, IsNilRequest, IsNilResponse, ReencryptRequest,
+    ReencryptResponse,
+};
+
+/// The key is unavailable for `lasting` at the start of every `every`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyOutage {
+    pub every: Duration,
+    pub lasting: Duration,
+}
+
+/// Which faults to inject. The default injects none; [`Chaos::new`] rejects
+/// rates outside `0.0..=1.0`.
+#[derive(Clone, Debug, Default)]
+pub struct Faults {
+    /// Each call is delayed by a uniform random duration up to this.
+    pub max_latency: Duration,
+    /// Probability that a call's response is dropped after the oracle ran it.
+    pub drop_rate: f64,
+    /// Probability that a call never answers after the oracle ran it, so the
+    /// client only gets out through its own deadline.
+    pub hang_rate: f64,
+    /// Probability that a request ciphertext has one bit flipped.
+    pub corrupt_rate: f64,
+    pub key_outage: Option<KeyOutage>,
+}
+
+/// A fault rate outside `0.0..=1.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InvalidRate {
+    pub field: &'static str,
+    pub rate: f64,
+}
+
+impl fmt::Display for InvalidRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} must lie in 0.0..=1.0, got {}", self.field, self.rate)
+    }
+}
+
+impl std::error::Error for InvalidRate {}
+
+pub struct Chaos<O> {
+    inner: O,
+    faults: Faults,
+    started: Instant,
+}
+
+impl<O: DecryptionOracle> Chaos<O> {
+    pub fn new(inner: O, faults: Faults) -> Result<Self, InvalidRate> {
+        for (field, rate) in [
+            ("drop_rate", faults.drop_rate),
+            ("hang_rate", faults.hang_rate),
+            ("corrupt_rate", faults.corrupt_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(InvalidRate { field, rate });
+            }
+        }
+        Ok(Self {
+            inner,
+            faults,
+            started: Instant::now(),
+        })
+    }
+
+    async fn before(&self) -> Result<(), Status> {
+        if !self.faults.max_latency.is_zero() {
+            let delay = rand::thread_rng().gen_range(Duration::ZERO..=self.faults.max_latency);
+            tokio::time::sleep(delay).await;
+        }
+        if let Some(outage) = self.faults.key_outage {
+            let elapsed = self.started.elapsed().as_nanos();
+            if !outage.every.is_zero()
+                && elapsed % outage.every.as_nanos() < outage.lasting.as_nanos()
+            {
+                return Err(Status::unavailable("decryption key unavailable"));
+            }
+        }
+        Ok(())
+    }
+
+    fn corrupt(&self, encrypted: &mut FheEncrypted) {
+        let mut rng = rand::thread_rng();
+        if encrypted.data.is_empty() || !rng.gen_bool(self.faults.corrupt_rate) {
+            return;
+        }
+        let idx = rng.gen_range(0..encrypted.data.len());
+        encrypted.data[idx] ^= 1 << rng.gen_range(0..8);
+    }
+
+    async fn after<T>(&self, response: Response<T>) -> Result<Response<T>, Status> {
+        let (hang, drop) = {
+            let mut rng = rand::thread_rng();
+            (
+                rng.gen_bool(self.faults.hang_rate),
+                rng.gen_bool(self.faults.drop_rate),
+            )
+        };
+        if hang {
+            std::future::pending::<()>().await;
+        }
+        if drop {
+            return Err(Status::unavailable("response dropped"));
+        }
+        Ok(response)
+    }
+}
+
+#[tonic::async_trait]
+impl<O: DecryptionOracle> DecryptionOracle for Chaos<O> {
+    async fn decrypt(
+        &self,
+        mut request: Request<DecryptRequest>,
+    ) -> Result<Response<DecryptResponse>, Status> {
+        self.before().await?;
+        if let Some(encrypted) = &mut request.get_mut().encrypted {
+            self.corrupt(encrypted);
+        }
+        let response = self.inner.decrypt(request).await?;
+        self.after(response).await
+    }
+
+    async fn reencrypt(
+        &self,
+        mut request: Request<ReencryptRequest>,
+    ) -> Result<Response<ReencryptResponse>, Status> {
+        self.before().await?;
+        if let Some(encrypted) = &mut request.get_mut().encrypted {
+            self.corrupt(encrypted);
+        }
+        let response = self.inner.reencrypt(request).await?;
+        self.after(response).await
+    }
+
+    async fn assert_is_nil(
+        &self,
+        mut request: Request<IsNilRequest>,
+    ) -> Result<Response<IsNilResponse>, Status> {
+        self.before().await?;
+        if let Some(encrypted) = &mut request.get_mut().encrypted {
+            self.corrupt(encrypted);
+        }
+        let response = self.inner.assert_is_nil(request).await?;
+        self.after(response).await
+    }
+
+    async fn aggregate(
+        &self,
+        mut request: Request<AggregateRequest>,
+    ) -> Result<Response<AggregateResponse>, Status> {
+        self.before().await?;
+        for encrypted in &mut request.get_mut().encrypted {
+            self.corrupt(encrypted);
+        }
+        let response = self.inner.aggregate(request).await?;
+        self.after(response).await
+    }
+
+    async fn bucket(
+        &self,
+        mut request: Request<BucketRequest>,
+    ) -> Result<Response<BucketResponse>, Status> {
+        self.before().await?;
+        if let Some(encrypted) = &mut request.get_mut().encrypted {
+            self.corrupt(encrypted);
+        }
+        let response = self.inner.bucket(request).await?;
+        self.after(response).await
+    }
+}
@@ -0,0 +1,169 @@
+//! Oracle client running [`Protocol`] over the generated tonic client.
+
+use std::fmt;
+
+use tonic::codegen::{Body, Bytes, StdError};
+use tonic::metadata::errors::InvalidMetadataValue;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Channel, Endpoint};
+use tonic::{Request, Status};
+
+use crate::delegation::DelegationToken;
+use crate::metadata;
+use crate::oracle::decryption_oracle_client::DecryptionOracleClient;
+use crate::oracle::{AggregateOp, FheEncrypted};
+use crate::protocol::{ProofSigner, Protocol, ProtocolError, SignatureVerifier};
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The call failed in transport or on the oracle.
+    Status(Status),
+    Protocol(ProtocolError),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Status(status) => write!(f, "oracle call failed: {status}"),
+            ClientError::Protocol(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<Status> for ClientError {
+    fn from(status: Status) -> Self {
+        ClientError::Status(status)
+    }
+}
+
+impl From<ProtocolError> for ClientError {
+    fn from(err: ProtocolError) -> Self {
+        ClientError::Protocol(err)
+    }
+}
+
+pub struct OracleClient<T, S, V> {
+    inner: DecryptionOracleClient<T>,
+    protocol: Protocol<S, V>,
+    api_key: Option<MetadataValue<Ascii>>,
+}
+
+impl<S, V> OracleClient<Channel, S, V>
+where
+    S: ProofSigner,
+    V: SignatureVerifier,
+{
+    pub async fn connect<D>(
+        dst: D,
+        protocol: Protocol<S, V>,
+    ) -> Result<Self, tonic::transport::Error>
+    where
+        D: TryInto<Endpoint>,
+        D::Error: Into<StdError>,
+    {
+        let inner = DecryptionOracleClient::connect(dst).await?;
+        Ok(Self::new(inner, protocol))
+    }
+}
+
+impl<T, S, V> OracleClient<T, S, V>
+where
+    T: tonic::client::GrpcService<tonic::body::BoxBody>,
+    T::Error: Into<StdError>,
+    T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+    <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    S: ProofSigner,
+    V: SignatureVerifier,
+{
+    pub fn new(inner: DecryptionOracleClient<T>, protocol: Protocol<S, V>) -> Self {
+        Self {
+            inner,
+            protocol,
+            api_key: None,
+        }
+    }
+
+    /// Sends `api_key` as `x-api-key` metadata on every call. Fails if it is
+    /// not printable ASCII.
+    pub fn with_api_key(mut self, api_key: &str) -> Result<Self, InvalidMetadataValue> {
+        self.api_key = Some(api_key.parse()?);
+        Ok(self)
+    }
+
+    fn request<M>(&self, message: M) -> Request<M> {
+        let mut request = Request::new(message);
+        if let Some(api_key) = &self.api_key {
+            request
+                .metadata_mut()
+                .insert(metadata::API_KEY, api_key.clone());
+        }
+        request
+    }
+
+    pub async fn decrypt(&mut self, encrypted: FheEncrypted) -> Result<String, ClientError> {
+        let request = self.protocol.decrypt(encrypted)?;
+        let response = self.inner.decrypt(self.request(request.clone()));
+        let response = response.await?.into_inner();
+        Ok(self.protocol.verify_decrypt(&request, response)?)
+    }
+
+    pub async fn reencrypt(
+        &mut self,
+        encrypted: FheEncrypted,
+        user_public_key: impl Into<String>,
+    ) -> Result<String, ClientError> {
+        let request = self.protocol.reencrypt(encrypted, user_public_key)?;
+        let response = self.inner.reencrypt(self.request(request.clone()));
+        let response = response.await?.into_inner();
+        Ok(self.protocol.verify_reencrypt(&request, response)?)
+    }
+
+    /// Like [`reencrypt`](Self::reencrypt), but presents `token` to show the
+    /// owner of the ciphertext delegated the call.
+    pub async fn reencrypt_delegated(
+        &mut self,
+        encrypted: FheEncrypted,
+        user_public_key: impl Into<String>,
+        token: &DelegationToken,
+    ) -> Result<String, ClientError> {
+        let request = self.protocol.reencrypt(encrypted, user_public_key)?;
+        let mut call = self.request(request.clone());
+        let token = token.to_hex().parse().expect("hex is valid metadata");
+        call.metadata_mut()
+            .insert(metadata::DELEGATION_TOKEN, token);
+        let response = self.inner.reencrypt(call).await?.into_inner();
+        Ok(self.protocol.verify_reencrypt(&request, response)?)
+    }
+
+    pub async fn is_nil(&mut self, encrypted: FheEncrypted) -> Result<bool, ClientError> {
+        let request = self.protocol.is_nil(encrypted)?;
+        let response = self.inner.assert_is_nil(self.request(request.clone()));
+        let response = response.await?.into_inner();
+        Ok(self.protocol.verify_is_nil(&request, response)?)
+    }
+
+    pub async fn aggregate(
+        &mut self,
+        encrypted: Vec<FheEncrypted>,
+        op: AggregateOp,
+        threshold: impl Into<String>,
+    ) -> Result<String, ClientError> {
+        let request = self.protocol.aggregate(encrypted, op, threshold)?;
+        let response = self.inner.aggregate(self.request(request.clone()));
+        let response = response.await?.into_inner();
+        Ok(self.protocol.verify_aggregate(&request, response)?)
+    }
+
+    pub async fn bucket(
+        &mut self,
+        encrypted: FheEncrypted,
+        bucket_set: impl Into<String>,
+    ) -> Result<u32, ClientError> {
+        let request = self.protocol.bucket(encrypted, bucket_set)?;
+        let response = self.inner.bucket(self.request(request.clone()));
+        let response = response.await?.into_inner();
+        Ok(self.protocol.verify_bucket(&request, response)?)
+    }
+}
@@ -0,0 +1,320 @@
+//! Capability-scoped delegation tokens for reencryption.
+//!
+//! A ciphertext owner issues a token under a root key shared with the oracle,
+//! scopes it to a ciphertext with [`Caveat::ciphertext`] and hands it to a
+//! third party, who may narrow it further with [`DelegationToken::attenuate`]
+//! before passing it on. The oracle's Reencrypt path checks the token with
+//! [`authorize_reencrypt`], so view access can be delegated without touching
+//! the on-chain ACL.
+//!
+//! Tokens follow the macaroon construction: the tag starts as an HMAC of the
+//! identifier under the root key and every caveat is chained into it, so
+//! caveats can be added by anyone holding a token but never removed.
+
+use std::fmt;
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::oracle::{FheEncrypted, ReencryptRequest};
+
+type HmacSha256 = Hmac<Sha256>;
+
+const VERSION: u8 = 1;
+const DOMAIN: &[u8] = b"oracle.delegation.v1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DelegationError {
+    Malformed,
+    BadTag,
+    /// The token names no ciphertext, so it would cover every one.
+    Unscoped,
+    /// The token's issuer does not own the ciphertext.
+    NotOwner,
+    CiphertextMismatch,
+    RecipientMismatch,
+    Expired,
+}
+
+impl fmt::Display for DelegationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DelegationError::Malformed => write!(f, "malformed delegation token"),
+            DelegationError::BadTag => write!(f, "delegation token was not issued by this key"),
+            DelegationError::Unscoped => {
+                write!(f, "delegation token is not scoped to a ciphertext")
+            }
+            DelegationError::NotOwner => {
+                write!(f, "delegation token issuer does not own this ciphertext")
+            }
+            DelegationError::CiphertextMismatch => {
+                write!(f, "delegation token does not cover this ciphertext")
+            }
+            DelegationError::RecipientMismatch => {
+                write!(f, "delegation token does not cover this recipient")
+            }
+            DelegationError::Expired => write!(f, "delegation token has expired"),
+        }
+    }
+}
+
+impl std::error::Error for DelegationError {}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl From<DelegationError> for tonic::Status {
+    fn from(err: DelegationError) -> Self {
+        match err {
+            DelegationError::Malformed => tonic::Status::invalid_argument(err.to_string()),
+            _ => tonic::Status::permission_denied(err.to_string()),
+        }
+    }
+}
+
+/// A restriction a token's holder cannot lift.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Caveat {
+    /// Only the ciphertext with this [`Caveat::ciphertext`] digest.
+    Ciphertext([u8; 32]),
+    /// Only reencryption to this (hex encoded) user public key.
+    Recipient(String),
+    /// Not after this unix time, in seconds.
+    ExpiresAt(u64),
+}
+
+fn ciphertext_digest(encrypted: &FheEncrypted) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(b"oracle.delegation.ciphertext")
+        .chain_update(encrypted.r#type.to_be_bytes())
+        .chain_update((encrypted.data.len() as u64).to_be_bytes())
+        .chain_update(&encrypted.data)
+        .finalize()
+        .into()
+}
+
+impl Caveat {
+    /// Commits to the type tag as well as the data, so the token does not
+    /// cover the same bytes reinterpreted as another type.
+    pub fn ciphertext(encrypted: &FheEncrypted) -> Self {
+        Caveat::Ciphertext(ciphertext_digest(encrypted))
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Caveat::Ciphertext(digest) => {
+                out.push(0);
+                out.extend_from_slice(digest);
+            }
+            Caveat::Recipient(key) => {
+                out.push(1);
+                put_str(out, key);
+            }
+            Caveat::ExpiresAt(secs) => {
+                out.push(2);
+                out.extend_from_slice(&secs.to_be_bytes());
+            }
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, DelegationError> {
+        match take(input, 1)?[0] {
+            0 => Ok(Caveat::Ciphertext(take(input, 32)?.try_into().unwrap())),
+            1 => Ok(Caveat::Recipient(take_str(input)?)),
+            2 => Ok(Caveat::ExpiresAt(u64::from_be_bytes(
+                take(input, 8)?.try_into().unwrap(),
+            ))),
+            _ => Err(DelegationError::Malformed),
+        }
+    }
+
+    fn check(&self, request: &ReencryptRequest, now: u64) -> Result<(), DelegationError> {
+        match self {
+            Caveat::Ciphertext(digest) => match &request.encrypted {
+                Some(encrypted) if ciphertext_digest(encrypted) == *digest => Ok(()),
+                _ => Err(DelegationError::CiphertextMismatch),
+            },
+            Caveat::Recipient(key) if *key != request.user_public_key => {
+                Err(DelegationError::RecipientMismatch)
+            }
+            Caveat::ExpiresAt(secs) if now > *secs => Err(DelegationError::Expired),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], DelegationError> {
+    if input.len() < len {
+        return Err(DelegationError::Malformed);
+    }
+    let (head, rest) = input.split_at(len);
+    *input = rest;
+    Ok(head)
+}
+
+fn take_str(input: &mut &[u8]) -> Result<String, DelegationError> {
+    let len = u32::from_be_bytes(take(input, 4)?.try_into().unwrap());
+    let bytes = take(input, len as usize)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| DelegationError::Malformed)
+}
+
+fn root(root_key: &[u8], identifier: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(root_key).expect("HMAC accepts any key length");
+    mac.update(DOMAIN);
+    mac.update(identifier.as_bytes());
+    mac
+}
+
+fn chain(tag: &[u8], caveat: &Caveat) -> HmacSha256 {
+    let mut encoded = Vec::new();
+    caveat.encode(&mut encoded);
+    let mut mac = HmacSha256::new_from_slice(tag).expect("HMAC accepts any key length");
+    mac.update(&encoded);
+    mac
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DelegationToken {
+    identifier: String,
+    caveats: Vec<Caveat>,
+    tag: [u8; 32],
+}
+
+impl DelegationToken {
+    /// Issues a token without caveats; `identifier` names the issuing owner
+    /// and tells the oracle which root key to verify it with. The oracle only
+    /// accepts it once it carries a [`Caveat::Ciphertext`].
+    pub fn issue(root_key: &[u8], identifier: impl Into<String>) -> Self {
+        let identifier = identifier.into();
+        Self {
+            tag: root(root_key, &identifier).finalize().into_bytes().into(),
+            identifier,
+            caveats: Vec::new(),
+        }
+    }
+
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    pub fn caveats(&self) -> &[Caveat] {
+        &self.caveats
+    }
+
+    /// Returns a copy of the token further restricted by `caveat`.
+    #[must_use]
+    pub fn attenuate(&self, caveat: Caveat) -> Self {
+        let mut token = self.clone();
+        token.tag = chain(&self.tag, &caveat).finalize().into_bytes().into();
+        token.caveats.push(caveat);
+        token
+    }
+
+    /// Checks that the token was issued under `root_key`, is scoped to a
+    /// ciphertext and that every caveat admits `request` at unix time `now`.
+    ///
+    /// A token only delegates what its issuer holds: `owns` is the oracle's
+    /// ACL lookup and must return whether the owner named by the identifier
+    /// owns the ciphertext.
+    pub fn verify_reencrypt(
+        &self,
+        root_key: &[u8],
+        request: &ReencryptRequest,
+        now: u64,
+        owns: impl FnOnce(&str, &FheEncrypted) -> bool,
+    ) -> Result<(), DelegationError> {
+        let mut mac = root(root_key, &self.identifier);
+        for caveat in &self.caveats {
+            mac = chain(&mac.finalize().into_bytes(), caveat);
+        }
+        mac.verify_slice(&self.tag)
+            .map_err(|_| DelegationError::BadTag)?;
+
+        if !self
+            .caveats
+            .iter()
+            .any(|caveat| matches!(caveat, Caveat::Ciphertext(_)))
+        {
+            return Err(DelegationError::Unscoped);
+        }
+        self.caveats
+            .iter()
+            .try_for_each(|caveat| caveat.check(request, now))?;
+
+        match &request.encrypted {
+            Some(encrypted) if owns(&self.identifier, encrypted) => Ok(()),
+            _ => Err(DelegationError::NotOwner),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = vec![VERSION];
+        put_str(&mut out, &self.identifier);
+        out.extend_from_slice(&(self.caveats.len() as u32).to_be_bytes());
+        for caveat in &self.caveats {
+            caveat.encode(&mut out);
+        }
+        out.extend_from_slice(&self.tag);
+        out
+    }
+
+    pub fn from_bytes(mut input: &[u8]) -> Result<Self, DelegationError> {
+        let input = &mut input;
+        if take(input, 1)?[0] != VERSION {
+            return Err(DelegationError::Malformed);
+        }
+        let identifier = take_str(input)?;
+        let count = u32::from_be_bytes(take(input, 4)?.try_into().unwrap());
+        let caveats = (0..count)
+            .map(|_| Caveat::decode(input))
+            .collect::<Result<_, _>>()?;
+        let tag = take(input, 32)?.try_into().unwrap();
+        if !input.is_empty() {
+            return Err(DelegationError::Malformed);
+        }
+        Ok(Self {
+            identifier,
+            caveats,
+            tag,
+        })
+    }
+
+    /// Hex encoding, as carried in the `x-delegation-token` metadata.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.to_bytes())
+    }
+
+    pub fn from_hex(token: &str) -> Result<Self, DelegationError> {
+        let bytes = hex::decode(token).map_err(|_| DelegationError::Malformed)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Checks the token a Reencrypt call carries in its `x-delegation-token`
+/// metadata and returns the identifier of the owner who delegated access.
+///
+/// `root_key` looks up the root key for a token identifier; `owns` is passed
+/// to [`DelegationToken::verify_reencrypt`]. Calls without a token are
+/// refused, so oracles that also serve owners directly must check the ACL
+/// before falling back to this.
+#[cfg(feature = "server")]
+pub fn authorize_reencrypt(
+    request: &tonic::Request<ReencryptRequest>,
+    root_key: impl FnOnce(&str) -> Option<Vec<u8>>,
+    owns: impl FnOnce(&str, &FheEncrypted) -> bool,
+    now: u64,
+) -> Result<String, tonic::Status> {
+    let token = crate::metadata::delegation_token(request).ok_or_else(|| {
+        tonic::Status::permission_denied(format!(
+            "missing {} metadata",
+            crate::metadata::DELEGATION_TOKEN
+        ))
+    })?;
+    let token = DelegationToken::from_hex(token)?;
+    let root_key = root_key(token.identifier()).ok_or(DelegationError::BadTag)?;
+    token.verify_reencrypt(&root_key, request.get_ref(), now, owns)?;
+    Ok(token.identifier)
+}
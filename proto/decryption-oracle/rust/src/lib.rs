@@ -1,8 +1,37 @@
+// Server-side hooks report failures as `tonic::Status`, like the generated handlers.
+#![allow(clippy::result_large_err)]
+
+#[cfg(feature = "server")]
+pub mod buckets;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(feature = "client")]
+pub mod client;
+pub mod delegation;
+#[cfg(feature = "server")]
+pub mod lineage;
+#[cfg(all(feature = "client", feature = "server"))]
+pub mod local;
+#[cfg(any(feature = "client", feature = "server"))]
+pub mod metadata;
 pub mod oracle;
+#[cfg(feature = "server")]
+pub mod postprocess;
+#[cfg(feature = "server")]
+pub mod proofcache;
+pub mod protocol;
+pub mod request;
+pub mod rpc;
+#[cfg(feature = "server")]
+pub mod tenant;
+#[cfg(feature = "server")]
+pub mod transcript;
 
+#[cfg(feature = "server")]
 pub use crate::oracle::decryption_oracle_server::{DecryptionOracle, DecryptionOracleServer};
+#[cfg(feature = "client")]
 pub use crate::oracle::decryption_oracle_client::{DecryptionOracleClient};
 pub use crate::oracle::{
-    DecryptRequest, DecryptResponse, IsNilRequest, IsNilResponse, ReencryptRequest,
-    ReencryptResponse,
+    AggregateRequest, AggregateResponse, BucketRequest, BucketResponse, DecryptRequest,
+    DecryptResponse, IsNilRequest, IsNilResponse, ReencryptRequest, ReencryptResponse,
 };
@@ -0,0 +1,123 @@
+//! Per-input release budgets enforced through ciphertext lineage.
+//!
+//! Whoever evaluates on ciphertexts reports each result with
+//! [`LineageRegistry::record`], so the registry knows which original inputs
+//! contributed to every handle. Decrypting a handle charges one release to the
+//! budget of each of those inputs; once any of them is spent the decryption is
+//! refused. Running a budgeted value through trivial arithmetic therefore does
+//! not buy extra releases.
+//!
+//! The registry fails closed: a handle that is neither a budgeted input nor a
+//! recorded output is never released, since nothing is known about what it
+//! was computed from.
+//!
+//! Handles are the SHA-256 digest of the ciphertext data.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use sha2::{Digest, Sha256};
+use tonic::Status;
+
+use crate::oracle::FheEncrypted;
+
+pub type Handle = [u8; 32];
+
+pub fn handle(data: &[u8]) -> Handle {
+    Sha256::digest(data).into()
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Original inputs each derived handle was computed from.
+    roots: HashMap<Handle, Arc<HashSet<Handle>>>,
+    /// Releases left per original input.
+    budgets: HashMap<Handle, u32>,
+}
+
+impl Inner {
+    fn roots_of(&self, handle: Handle) -> Result<Arc<HashSet<Handle>>, Status> {
+        if let Some(roots) = self.roots.get(&handle) {
+            return Ok(roots.clone());
+        }
+        if self.budgets.contains_key(&handle) {
+            return Ok(Arc::new(HashSet::from([handle])));
+        }
+        Err(Status::failed_precondition(
+            "ciphertext is neither a budgeted input nor a recorded output",
+        ))
+    }
+
+    fn roots_of_all(
+        &self,
+        handles: impl IntoIterator<Item = Handle>,
+    ) -> Result<HashSet<Handle>, Status> {
+        let mut roots = HashSet::new();
+        for handle in handles {
+            roots.extend(self.roots_of(handle)?.iter().copied());
+        }
+        Ok(roots)
+    }
+}
+
+#[derive(Default)]
+pub struct LineageRegistry {
+    inner: Mutex<Inner>,
+}
+
+impl LineageRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `input` as an original allowing `releases` more decryptions
+    /// of values derived from it.
+    pub fn set_budget(&self, input: Handle, releases: u32) {
+        self.inner.lock().unwrap().budgets.insert(input, releases);
+    }
+
+    /// Releases left for `input`, or `None` if it is not a budgeted input.
+    pub fn remaining(&self, input: Handle) -> Option<u32> {
+        self.inner.lock().unwrap().budgets.get(&input).copied()
+    }
+
+    /// Records that `output` was computed from `inputs`, each of which must
+    /// be a budgeted input or a recorded output. An output computed from
+    /// nothing would be released without limit, so `inputs` may not be empty.
+    pub fn record(&self, output: Handle, inputs: &[Handle]) -> Result<(), Status> {
+        if inputs.is_empty() {
+            return Err(Status::invalid_argument(
+                "an output needs at least one input",
+            ));
+        }
+        let mut inner = self.inner.lock().unwrap();
+        let roots = inner.roots_of_all(inputs.iter().copied())?;
+        inner.roots.insert(output, Arc::new(roots));
+        Ok(())
+    }
+
+    /// Drops the lineage of `output` once its ciphertext is discarded.
+    pub fn forget(&self, output: Handle) {
+        self.inner.lock().unwrap().roots.remove(&output);
+    }
+
+    /// Charges one release to every input behind `encrypted`.
+    ///
+    /// Nothing is charged unless every handle is known and every budget
+    /// involved can pay.
+    pub fn charge(&self, encrypted: &[FheEncrypted]) -> Result<(), Status> {
+        let mut inner = self.inner.lock().unwrap();
+        let roots = inner.roots_of_all(encrypted.iter().map(|e| handle(&e.data)))?;
+        if roots.iter().any(|root| inner.budgets.get(root) == Some(&0)) {
+            return Err(Status::resource_exhausted(
+                "release budget of a contributing input is spent",
+            ));
+        }
+        for root in &roots {
+            if let Some(budget) = inner.budgets.get_mut(root) {
+                *budget -= 1;
+            }
+        }
+        Ok(())
+    }
+}
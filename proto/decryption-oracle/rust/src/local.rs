@@ -0,0 +1,329 @@
+//! In-process oracle for local development.
+//!
+//! [`LocalOracle`] answers oracle calls with developer keys and signs its
+//! responses like a real oracle would. [`OracleClient::local`] runs the normal
+//! client against it without a server or network, so switching to a deployed
+//! oracle only means replacing that constructor with
+//! [`OracleClient::connect`].
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tonic::{Request, Response, Status};
+
+use crate::buckets::{BucketSet, BucketSets};
+use crate::client::OracleClient;
+use crate::delegation::authorize_reencrypt;
+use crate::lineage::LineageRegistry;
+use crate::oracle::decryption_oracle_client::DecryptionOracleClient;
+use crate::oracle::decryption_oracle_server::{DecryptionOracle, DecryptionOracleServer};
+use crate::oracle::{
+    AggregateOp, AggregateRequest, AggregateResponse, BucketRequest, BucketResponse,
+    DecryptRequest, DecryptResponse, EncryptedType, FheEncrypted, IsNilRequest, IsNilResponse,
+    ReencryptRequest, ReencryptResponse,
+};
+use crate::protocol::{
+    aggregate_response_payload, bucket_response_payload, decrypt_response_payload,
+    is_nil_response_payload, reencrypt_response_payload, ProofSigner, Protocol, SignatureVerifier,
+};
+use crate::request::MIN_AGGREGATE_SIZE;
+
+/// Keys a [`LocalOracle`] decrypts with.
+pub trait DevKeys: Send + Sync + 'static {
+    /// Returns the plaintext in decimal.
+    fn decrypt(&self, encrypted: &FheEncrypted) -> Result<String, Status>;
+
+    fn reencrypt(&self, encrypted: &FheEncrypted, user_public_key: &str) -> Result<String, Status>;
+}
+
+/// Keys that provide no secrecy at all: a ciphertext is the plaintext in
+/// little-endian bytes, and reencryption returns it hex encoded. For tests
+/// and demos only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MockKeys;
+
+/// Width of `ty` in bits.
+fn bits(ty: EncryptedType) -> u32 {
+    match ty {
+        EncryptedType::Uint8 => 8,
+        EncryptedType::Uint16 => 16,
+        EncryptedType::Uint32 => 32,
+        EncryptedType::Uint64 => 64,
+        EncryptedType::Uint128 => 128,
+        EncryptedType::Uint256 => 256,
+    }
+}
+
+impl MockKeys {
+    /// Fails if `value` does not fit in `ty`.
+    pub fn encrypt(value: u128, ty: EncryptedType) -> Result<FheEncrypted, Status> {
+        let bits = bits(ty);
+        if bits < 128 && value >> bits != 0 {
+            return Err(Status::out_of_range(format!(
+                "{value} does not fit in {}",
+                ty.as_str_name()
+            )));
+        }
+        let mut data = value.to_le_bytes().to_vec();
+        data.resize(bits as usize / 8, 0);
+        Ok(FheEncrypted::new(data, ty))
+    }
+}
+
+impl DevKeys for MockKeys {
+    fn decrypt(&self, encrypted: &FheEncrypted) -> Result<String, Status> {
+        let (low, high) = encrypted.data.split_at(encrypted.data.len().min(16));
+        if high.iter().any(|byte| *byte != 0) {
+            return Err(Status::out_of_range(
+                "mock ciphertext does not fit in 128 bits",
+            ));
+        }
+        let mut bytes = [0; 16];
+        bytes[..low.len()].copy_from_slice(low);
+        Ok(u128::from_le_bytes(bytes).to_string())
+    }
+
+    fn reencrypt(
+        &self,
+        encrypted: &FheEncrypted,
+        _user_public_key: &str,
+    ) -> Result<String, Status> {
+        Ok(hex::encode(&encrypted.data))
+    }
+}
+
+fn plaintext(keys: &impl DevKeys, encrypted: &FheEncrypted) -> Result<u128, Status> {
+    keys.decrypt(encrypted)?
+        .parse()
+        .map_err(|_| Status::out_of_range("value does not fit in 128 bits"))
+}
+
+/// Sums `values` of type `ty` modulo 2^n like homomorphic addition does.
+fn wrapping_sum(values: &[u128], ty: EncryptedType) -> Result<u128, Status> {
+    match bits(ty) {
+        256 => values
+            .iter()
+            .try_fold(0u128, |sum, value| sum.checked_add(*value))
+            .ok_or_else(|| Status::out_of_range("mock sum does not fit in 128 bits")),
+        128 => Ok(values.iter().fold(0, |sum, value| sum.wrapping_add(*value))),
+        bits => {
+            let sum = values
+                .iter()
+                .fold(0u128, |sum, value| sum.wrapping_add(*value));
+            Ok(sum & ((1 << bits) - 1))
+        }
+    }
+}
+
+/// Sums `values` without wrapping, as Mean does.
+fn exact_sum(values: &[u128]) -> Result<u128, Status> {
+    values
+        .iter()
+        .try_fold(0u128, |sum, value| sum.checked_add(*value))
+        .ok_or_else(|| Status::out_of_range("mock sum does not fit in 128 bits"))
+}
+
+/// Key id the bucket sets of a [`LocalOracle`] are configured under.
+pub const LOCAL_KEY_ID: &str = "local";
+
+/// An oracle answering in-process with developer keys.
+///
+/// `sign` produces the oracle signature over each response payload; the
+/// client's [`SignatureVerifier`] must accept it.
+pub struct LocalOracle<K, F> {
+    keys: K,
+    sign: F,
+    min_aggregate_size: usize,
+    /// Root key and identifier of the owner of every ciphertext.
+    delegation: Option<(Vec<u8>, String)>,
+    bucket_sets: BucketSets,
+    lineage: Option<Arc<LineageRegistry>>,
+}
+
+impl<K, F> LocalOracle<K, F>
+where
+    K: DevKeys,
+    F: Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    pub fn new(keys: K, sign: F) -> Self {
+        Self {
+            keys,
+            sign,
+            min_aggregate_size: MIN_AGGREGATE_SIZE,
+            delegation: None,
+            bucket_sets: BucketSets::new(),
+            lineage: None,
+        }
+    }
+
+    /// Configures the bucket set Bucket calls name as `id`, under
+    /// [`LOCAL_KEY_ID`].
+    #[must_use]
+    pub fn with_bucket_set(self, id: impl Into<String>, set: BucketSet) -> Self {
+        self.bucket_sets.set(LOCAL_KEY_ID, id, set);
+        self
+    }
+
+    /// Charges every input of Decrypt, AssertIsNil, Aggregate and Bucket calls
+    /// to `lineage`, refusing calls once a contributing budget is spent.
+    #[must_use]
+    pub fn with_lineage(mut self, lineage: Arc<LineageRegistry>) -> Self {
+        self.lineage = Some(lineage);
+        self
+    }
+
+    fn charge(&self, encrypted: &[FheEncrypted]) -> Result<(), Status> {
+        match &self.lineage {
+            Some(lineage) => lineage.charge(encrypted),
+            None => Ok(()),
+        }
+    }
+
+    /// Requires Reencrypt calls to carry a delegation token issued by `owner`
+    /// under `root_key`; `owner` is taken to own every ciphertext.
+    #[must_use]
+    pub fn with_delegation(
+        mut self,
+        root_key: impl Into<Vec<u8>>,
+        owner: impl Into<String>,
+    ) -> Self {
+        self.delegation = Some((root_key.into(), owner.into()));
+        self
+    }
+
+    /// Refuses aggregates over fewer than `size` values. Sizes below
+    /// [`MIN_AGGREGATE_SIZE`] are raised to it.
+    #[must_use]
+    pub fn with_min_aggregate_size(mut self, size: usize) -> Self {
+        self.min_aggregate_size = size.max(MIN_AGGREGATE_SIZE);
+        self
+    }
+}
+
+#[tonic::async_trait]
+impl<K, F> DecryptionOracle for LocalOracle<K, F>
+where
+    K: DevKeys,
+    F: Fn(&[u8]) -> String + Send + Sync + 'static,
+{
+    async fn decrypt(
+        &self,
+        request: Request<DecryptRequest>,
+    ) -> Result<Response<DecryptResponse>, Status> {
+        let request = request.into_inner();
+        request.validate()?;
+        let encrypted = request.encrypted.as_ref().unwrap();
+        self.charge(std::slice::from_ref(encrypted))?;
+        let mut response = DecryptResponse {
+            decrypted: self.keys.decrypt(encrypted)?,
+            signature: String::new(),
+        };
+        response.signature = (self.sign)(&decrypt_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn reencrypt(
+        &self,
+        request: Request<ReencryptRequest>,
+    ) -> Result<Response<ReencryptResponse>, Status> {
+        if let Some((root_key, owner)) = &self.delegation {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs());
+            authorize_reencrypt(
+                &request,
+                |identifier| (identifier == owner).then(|| root_key.clone()),
+                |identifier, _| identifier == owner,
+                now,
+            )?;
+        }
+        let request = request.into_inner();
+        request.validate()?;
+        let encrypted = request.encrypted.as_ref().unwrap();
+        let mut response = ReencryptResponse {
+            reencrypted: self.keys.reencrypt(encrypted, &request.user_public_key)?,
+            signature: String::new(),
+        };
+        response.signature = (self.sign)(&reencrypt_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn assert_is_nil(
+        &self,
+        request: Request<IsNilRequest>,
+    ) -> Result<Response<IsNilResponse>, Status> {
+        let request = request.into_inner();
+        request.validate()?;
+        let encrypted = request.encrypted.as_ref().unwrap();
+        self.charge(std::slice::from_ref(encrypted))?;
+        let mut response = IsNilResponse {
+            is_nil: self.keys.decrypt(encrypted)? == "0",
+            signature: String::new(),
+        };
+        response.signature = (self.sign)(&is_nil_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn aggregate(
+        &self,
+        request: Request<AggregateRequest>,
+    ) -> Result<Response<AggregateResponse>, Status> {
+        let request = request.into_inner();
+        request.validate_min_size(self.min_aggregate_size)?;
+        self.charge(&request.encrypted)?;
+        let ty = request.encrypted[0].encrypted_type()?;
+        let values = request
+            .encrypted
+            .iter()
+            .map(|encrypted| plaintext(&self.keys, encrypted))
+            .collect::<Result<Vec<_>, _>>()?;
+        let aggregate = match request.op() {
+            AggregateOp::Sum => wrapping_sum(&values, ty)?,
+            AggregateOp::Mean => exact_sum(&values)? / values.len() as u128,
+            AggregateOp::CountAbove => {
+                let threshold: u128 = request
+                    .threshold
+                    .parse()
+                    .map_err(|_| Status::out_of_range("threshold does not fit in 128 bits"))?;
+                values.iter().filter(|value| **value > threshold).count() as u128
+            }
+        };
+        let mut response = AggregateResponse {
+            aggregate: aggregate.to_string(),
+            signature: String::new(),
+        };
+        response.signature = (self.sign)(&aggregate_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn bucket(
+        &self,
+        request: Request<BucketRequest>,
+    ) -> Result<Response<BucketResponse>, Status> {
+        let request = request.into_inner();
+        request.validate()?;
+        let set = self.bucket_sets.get(LOCAL_KEY_ID, &request.bucket_set)?;
+        let encrypted = request.encrypted.as_ref().unwrap();
+        self.charge(std::slice::from_ref(encrypted))?;
+        let value = plaintext(&self.keys, encrypted)?;
+        let mut response = BucketResponse {
+            bucket: set.bucket(value),
+            signature: String::new(),
+        };
+        response.signature = (self.sign)(&bucket_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+}
+
+impl<O, S, V> OracleClient<DecryptionOracleServer<O>, S, V>
+where
+    O: DecryptionOracle,
+    S: ProofSigner,
+    V: SignatureVerifier,
+{
+    /// A client calling `oracle` in-process instead of over the network.
+    pub fn local(oracle: O, protocol: Protocol<S, V>) -> Self {
+        let inner = DecryptionOracleClient::new(DecryptionOracleServer::new(oracle));
+        Self::new(inner, protocol)
+    }
+}
@@ -0,0 +1,15 @@
+use tonic::Request;
+
+/// Metadata key carrying the caller's API key.
+pub const API_KEY: &str = "x-api-key";
+
+/// Metadata key carrying a hex encoded
+/// [`DelegationToken`](crate::delegation::DelegationToken) on Reencrypt calls.
+pub const DELEGATION_TOKEN: &str = "x-delegation-token";
+
+pub fn delegation_token<T>(request: &Request<T>) -> Option<&str> {
+    request
+        .metadata()
+        .get(DELEGATION_TOKEN)
+        .and_then(|value| value.to_str().ok())
+}
@@ -67,6 +67,66 @@ pub struct ReencryptResponse {
     #[prost(string, tag = "2")]
     pub signature: ::prost::alloc::string::String,
 }
+/// The request message containing the encrypted values to aggregate
+/// homomorphically, the aggregate to compute and the decimal threshold used by
+/// CountAbove, plus some proof (for future use).
+///
+/// All values must share one EncryptedType. Sum wraps modulo 2^n for that
+/// n-bit type, as homomorphic addition does. Mean adds the values in
+/// n + ceil(log2(count)) bits, so the sum cannot wrap, and divides by the
+/// number of values, rounding down.
+///
+/// Oracles refuse fewer values than their configured minimum, which is never
+/// below 2, and the same ciphertext given twice. These checks only reject
+/// malformed input and do not protect individual values: padding a set with
+/// known values, differencing overlapping sets or bisecting a CountAbove
+/// threshold still reveals any one of them. Oracles that must protect
+/// individual values charge every input against a per-input release budget.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AggregateRequest {
+    #[prost(message, repeated, tag = "1")]
+    pub encrypted: ::prost::alloc::vec::Vec<FheEncrypted>,
+    #[prost(enumeration = "AggregateOp", tag = "2")]
+    pub op: i32,
+    #[prost(string, tag = "3")]
+    pub threshold: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub proof: ::prost::alloc::string::String,
+}
+/// The response message containing only the decrypted aggregate
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AggregateResponse {
+    #[prost(string, tag = "1")]
+    pub aggregate: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub signature: ::prost::alloc::string::String,
+}
+/// The request message containing an encrypted value and the id of a bucket
+/// set the oracle has configured for the value's key, plus some proof (for
+/// future use). Callers cannot supply bounds of their own, so they cannot
+/// narrow the buckets down to the value itself.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BucketRequest {
+    #[prost(message, optional, tag = "1")]
+    pub encrypted: ::core::option::Option<FheEncrypted>,
+    #[prost(string, tag = "2")]
+    pub bucket_set: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub proof: ::prost::alloc::string::String,
+}
+/// The response message containing how many bounds of the bucket set the value
+/// is at or above, so 0 means below the first bound, never the value itself
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BucketResponse {
+    #[prost(uint32, tag = "1")]
+    pub bucket: u32,
+    #[prost(string, tag = "2")]
+    pub signature: ::prost::alloc::string::String,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum EncryptedType {
@@ -105,7 +165,37 @@ impl EncryptedType {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AggregateOp {
+    Sum = 0,
+    CountAbove = 1,
+    Mean = 2,
+}
+impl AggregateOp {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AggregateOp::Sum => "Sum",
+            AggregateOp::CountAbove => "CountAbove",
+            AggregateOp::Mean => "Mean",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Sum" => Some(Self::Sum),
+            "CountAbove" => Some(Self::CountAbove),
+            "Mean" => Some(Self::Mean),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
+#[cfg(feature = "client")]
 pub mod decryption_oracle_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
     use tonic::codegen::*;
@@ -264,9 +354,57 @@ pub mod decryption_oracle_client {
                 .insert(GrpcMethod::new("oracle.DecryptionOracle", "AssertIsNil"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn aggregate(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AggregateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AggregateResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/oracle.DecryptionOracle/Aggregate",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("oracle.DecryptionOracle", "Aggregate"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn bucket(
+            &mut self,
+            request: impl tonic::IntoRequest<super::BucketRequest>,
+        ) -> std::result::Result<tonic::Response<super::BucketResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/oracle.DecryptionOracle/Bucket",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("oracle.DecryptionOracle", "Bucket"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
+#[cfg(feature = "server")]
 pub mod decryption_oracle_server {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
     use tonic::codegen::*;
@@ -289,6 +427,17 @@ pub mod decryption_oracle_server {
             &self,
             request: tonic::Request<super::IsNilRequest>,
         ) -> std::result::Result<tonic::Response<super::IsNilResponse>, tonic::Status>;
+        async fn aggregate(
+            &self,
+            request: tonic::Request<super::AggregateRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::AggregateResponse>,
+            tonic::Status,
+        >;
+        async fn bucket(
+            &self,
+            request: tonic::Request<super::BucketRequest>,
+        ) -> std::result::Result<tonic::Response<super::BucketResponse>, tonic::Status>;
     }
     /// The decryption oracle service definition.
     #[derive(Debug)]
@@ -509,6 +658,98 @@ pub mod decryption_oracle_server {
                     };
                     Box::pin(fut)
                 }
+                "/oracle.DecryptionOracle/Aggregate" => {
+                    #[allow(non_camel_case_types)]
+                    struct AggregateSvc<T: DecryptionOracle>(pub Arc<T>);
+                    impl<
+                        T: DecryptionOracle,
+                    > tonic::server::UnaryService<super::AggregateRequest>
+                    for AggregateSvc<T> {
+                        type Response = super::AggregateResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AggregateRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DecryptionOracle>::aggregate(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AggregateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/oracle.DecryptionOracle/Bucket" => {
+                    #[allow(non_camel_case_types)]
+                    struct BucketSvc<T: DecryptionOracle>(pub Arc<T>);
+                    impl<
+                        T: DecryptionOracle,
+                    > tonic::server::UnaryService<super::BucketRequest>
+                    for BucketSvc<T> {
+                        type Response = super::BucketResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::BucketRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as DecryptionOracle>::bucket(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = BucketSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
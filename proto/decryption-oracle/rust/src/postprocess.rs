@@ -0,0 +1,355 @@
+//! Plaintext post-processing for decrypt responses.
+//!
+//! Deployments that must never release exact plaintexts configure a [`Chain`]
+//! of [`PostProcessor`]s per key or tenant. Servers run the chain over the
+//! decrypted value before signing the
+//! [`DecryptResponse`](crate::DecryptResponse), so the signature covers what
+//! the caller actually receives.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use rand::Rng;
+use tonic::Status;
+
+use crate::oracle::EncryptedType;
+use crate::tenant::Admission;
+
+/// A transformation applied to a decrypted value before it is released.
+pub trait PostProcessor: Send + Sync {
+    /// `key_id` names the key the value was decrypted with.
+    fn process(&self, key_id: &str, ty: EncryptedType, value: u128) -> Result<u128, Status>;
+}
+
+/// Rounds values down to a multiple of `step`.
+#[derive(Clone, Copy, Debug)]
+pub struct RoundDown {
+    step: u128,
+}
+
+impl RoundDown {
+    /// Returns `None` if `step` is zero.
+    pub fn new(step: u128) -> Option<Self> {
+        (step != 0).then_some(Self { step })
+    }
+}
+
+impl PostProcessor for RoundDown {
+    fn process(&self, _key_id: &str, _ty: EncryptedType, value: u128) -> Result<u128, Status> {
+        Ok(value - value % self.step)
+    }
+}
+
+/// Replaces values with the lower bound of the range they fall into.
+///
+/// Values below the first bound are released as zero.
+#[derive(Clone, Debug)]
+pub struct Bucketize {
+    bounds: Vec<u128>,
+}
+
+impl Bucketize {
+    pub fn new(mut bounds: Vec<u128>) -> Self {
+        bounds.sort_unstable();
+        bounds.dedup();
+        Self { bounds }
+    }
+}
+
+impl PostProcessor for Bucketize {
+    fn process(&self, _key_id: &str, _ty: EncryptedType, value: u128) -> Result<u128, Status> {
+        let idx = self.bounds.partition_point(|bound| *bound <= value);
+        Ok(if idx == 0 { 0 } else { self.bounds[idx - 1] })
+    }
+}
+
+/// Differential-privacy budget, spent by every release it is charged for.
+#[derive(Debug)]
+pub struct PrivacyBudget {
+    total: f64,
+    spent: Mutex<f64>,
+}
+
+impl PrivacyBudget {
+    /// Returns `None` unless `epsilon` is finite and not negative.
+    pub fn new(epsilon: f64) -> Option<Self> {
+        (epsilon.is_finite() && epsilon >= 0.0).then(|| Self {
+            total: epsilon,
+            spent: Mutex::new(0.0),
+        })
+    }
+
+    pub fn remaining(&self) -> f64 {
+        (self.total - *self.spent.lock().unwrap()).max(0.0)
+    }
+
+    fn charge(&self, epsilon: f64) -> Result<(), Status> {
+        let mut spent = self.spent.lock().unwrap();
+        if *spent + epsilon > self.total {
+            return Err(Status::resource_exhausted("privacy budget exhausted"));
+        }
+        *spent += epsilon;
+        Ok(())
+    }
+}
+
+/// Privacy budgets keyed by key id.
+///
+/// Every release under a key is charged to that key's budget, whichever
+/// tenant asked for it. Releases under a key without a budget are refused.
+#[derive(Debug, Default)]
+pub struct PrivacyBudgets {
+    budgets: RwLock<HashMap<String, Arc<PrivacyBudget>>>,
+}
+
+impl PrivacyBudgets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the budget of `key_id`, forgetting what was spent.
+    pub fn set(&self, key_id: impl Into<String>, budget: PrivacyBudget) {
+        self.budgets
+            .write()
+            .unwrap()
+            .insert(key_id.into(), Arc::new(budget));
+    }
+
+    pub fn remaining(&self, key_id: &str) -> Option<f64> {
+        let budgets = self.budgets.read().unwrap();
+        budgets.get(key_id).map(|budget| budget.remaining())
+    }
+
+    fn charge(&self, key_id: &str, epsilon: f64) -> Result<(), Status> {
+        let budget = self.budgets.read().unwrap().get(key_id).cloned();
+        budget
+            .ok_or_else(|| {
+                Status::failed_precondition(format!("no privacy budget for key {key_id}"))
+            })?
+            .charge(epsilon)
+    }
+}
+
+/// Bernoulli(`num` / `den`), for `num <= den`.
+fn bernoulli(rng: &mut impl Rng, num: u128, den: u128) -> bool {
+    rng.gen_range(0..den) < num
+}
+
+/// Bernoulli(exp(-`num` / `den`)), sampled exactly.
+fn bernoulli_exp(rng: &mut impl Rng, mut num: u128, den: u128) -> bool {
+    while num > den {
+        if !bernoulli_exp(rng, 1, 1) {
+            return false;
+        }
+        num -= den;
+    }
+    let mut k = 1;
+    while bernoulli(rng, num, den * k) {
+        k += 1;
+    }
+    k % 2 == 1
+}
+
+/// Adds discrete Laplace noise scaled to `sensitivity / epsilon`, charging
+/// `epsilon` to the key's budget per release.
+///
+/// Noise is sampled exactly with rational arithmetic (Canonne, Kamath and
+/// Steinke, 2020), so no floating-point rounding shapes the distribution.
+/// Noisy values are clamped to the range of the encrypted type.
+#[derive(Clone, Debug)]
+pub struct DiscreteLaplace {
+    epsilon: f64,
+    /// The noise is `x` with probability proportional to `exp(-|x| s / t)`.
+    s: u128,
+    t: u128,
+    budgets: Arc<PrivacyBudgets>,
+}
+
+impl DiscreteLaplace {
+    /// `epsilon` is the fraction `epsilon_num / epsilon_den`. Returns `None`
+    /// if any argument is zero.
+    pub fn new(
+        epsilon_num: u32,
+        epsilon_den: u32,
+        sensitivity: u32,
+        budgets: Arc<PrivacyBudgets>,
+    ) -> Option<Self> {
+        if epsilon_num == 0 || epsilon_den == 0 || sensitivity == 0 {
+            return None;
+        }
+        Some(Self {
+            epsilon: f64::from(epsilon_num) / f64::from(epsilon_den),
+            s: epsilon_num.into(),
+            t: u128::from(sensitivity) * u128::from(epsilon_den),
+            budgets,
+        })
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> i128 {
+        loop {
+            let u = rng.gen_range(0..self.t);
+            if !bernoulli_exp(rng, u, self.t) {
+                continue;
+            }
+            let mut v = 0;
+            while bernoulli_exp(rng, 1, 1) {
+                v += 1;
+            }
+            let magnitude = ((u + self.t * v) / self.s) as i128;
+            let negative = rng.gen::<bool>();
+            if negative && magnitude == 0 {
+                continue;
+            }
+            return if negative { -magnitude } else { magnitude };
+        }
+    }
+}
+
+fn max_value(ty: EncryptedType) -> u128 {
+    match ty {
+        EncryptedType::Uint8 => u8::MAX.into(),
+        EncryptedType::Uint16 => u16::MAX.into(),
+        EncryptedType::Uint32 => u32::MAX.into(),
+        EncryptedType::Uint64 => u64::MAX.into(),
+        EncryptedType::Uint128 | EncryptedType::Uint256 => u128::MAX,
+    }
+}
+
+impl PostProcessor for DiscreteLaplace {
+    fn process(&self, key_id: &str, ty: EncryptedType, value: u128) -> Result<u128, Status> {
+        self.budgets.charge(key_id, self.epsilon)?;
+        let noise = self.sample(&mut rand::thread_rng());
+        let noisy = if noise < 0 {
+            value.saturating_sub(noise.unsigned_abs())
+        } else {
+            value.saturating_add(noise.unsigned_abs())
+        };
+        Ok(noisy.min(max_value(ty)))
+    }
+}
+
+/// An ordered list of post-processors applied one after another.
+#[derive(Clone, Default)]
+pub struct Chain {
+    processors: Vec<Arc<dyn PostProcessor>>,
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn then(mut self, processor: impl PostProcessor + 'static) -> Self {
+        self.processors.push(Arc::new(processor));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    /// Runs the chain over a decimal `decrypted` value decrypted under `key_id`.
+    ///
+    /// An empty chain returns the value untouched, so wide types pass through
+    /// unparsed; a non-empty chain rejects values that do not fit in a `u128`.
+    pub fn apply(
+        &self,
+        key_id: &str,
+        ty: EncryptedType,
+        decrypted: &str,
+    ) -> Result<String, Status> {
+        if self.is_empty() {
+            return Ok(decrypted.to_owned());
+        }
+        let mut value: u128 = decrypted.parse().map_err(|_| {
+            Status::out_of_range(format!(
+                "{} value cannot be post-processed",
+                ty.as_str_name()
+            ))
+        })?;
+        for processor in &self.processors {
+            value = processor.process(key_id, ty, value)?;
+        }
+        Ok(value.to_string())
+    }
+}
+
+#[derive(Default)]
+struct Chains {
+    keys: HashMap<String, Chain>,
+    tenants: HashMap<String, Chain>,
+}
+
+/// Post-processing configuration keyed by key and by tenant.
+///
+/// Chains are looked up for an [`Admission`], so only callers the
+/// [`TenantRegistry`](crate::tenant::TenantRegistry) authenticated as a known
+/// tenant are ever processed. The chain of the key, or the default chain for
+/// keys without one, always runs; a tenant's own chain runs after it, so it
+/// can add protections but never remove those of the key. Chains can be
+/// replaced while the server runs.
+#[derive(Default)]
+pub struct PostProcessors {
+    default: Chain,
+    chains: RwLock<Chains>,
+}
+
+impl PostProcessors {
+    pub fn new(default: Chain) -> Self {
+        Self {
+            default,
+            chains: RwLock::default(),
+        }
+    }
+
+    pub fn set_key(&self, key_id: impl Into<String>, chain: Chain) {
+        self.chains
+            .write()
+            .unwrap()
+            .keys
+            .insert(key_id.into(), chain);
+    }
+
+    pub fn remove_key(&self, key_id: &str) -> Option<Chain> {
+        self.chains.write().unwrap().keys.remove(key_id)
+    }
+
+    pub fn set_tenant(&self, tenant: impl Into<String>, chain: Chain) {
+        self.chains
+            .write()
+            .unwrap()
+            .tenants
+            .insert(tenant.into(), chain);
+    }
+
+    pub fn remove_tenant(&self, tenant: &str) -> Option<Chain> {
+        self.chains.write().unwrap().tenants.remove(tenant)
+    }
+
+    /// The chain of the admitted key, or the default, followed by the chain of
+    /// the admitted tenant.
+    pub fn chain_for(&self, admission: &Admission) -> Chain {
+        let chains = self.chains.read().unwrap();
+        let mut chain = chains
+            .keys
+            .get(&admission.config.key_id)
+            .unwrap_or(&self.default)
+            .clone();
+        if let Some(tenant) = chains.tenants.get(&admission.tenant) {
+            chain.processors.extend(tenant.processors.iter().cloned());
+        }
+        chain
+    }
+
+    /// Applies the chain configured for an admitted call.
+    pub fn apply(
+        &self,
+        admission: &Admission,
+        ty: EncryptedType,
+        decrypted: &str,
+    ) -> Result<String, Status> {
+        self.chain_for(admission)
+            .apply(&admission.config.key_id, ty, decrypted)
+    }
+}
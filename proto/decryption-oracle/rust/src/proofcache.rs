@@ -0,0 +1,162 @@
+//! Bounded cache of decryption and reencryption proofs.
+//!
+//! Proof generation dominates response latency, and the same ciphertext is
+//! often decrypted again in later blocks. [`ProofCache`] keeps generated
+//! proofs keyed by a commitment to the request and the key epoch they were
+//! produced under, and drops them once they are older than the TTL or the
+//! cache holds more than its byte budget, oldest first.
+
+use std::collections::{BTreeMap, HashMap};
+use std::mem;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+use tonic::Status;
+
+use crate::oracle::FheEncrypted;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ProofKey {
+    commitment: [u8; 32],
+    epoch: u64,
+}
+
+impl ProofKey {
+    pub fn decrypt(encrypted: &FheEncrypted, epoch: u64) -> Self {
+        let commitment = Sha256::new()
+            .chain_update(b"oracle.proof.decrypt")
+            .chain_update(encrypted.r#type.to_be_bytes())
+            .chain_update((encrypted.data.len() as u64).to_be_bytes())
+            .chain_update(&encrypted.data)
+            .finalize()
+            .into();
+        Self { commitment, epoch }
+    }
+
+    /// Reencryption proofs also commit to the recipient key.
+    pub fn reencrypt(encrypted: &FheEncrypted, user_public_key: &str, epoch: u64) -> Self {
+        let commitment = Sha256::new()
+            .chain_update(b"oracle.proof.reencrypt")
+            .chain_update(encrypted.r#type.to_be_bytes())
+            .chain_update((encrypted.data.len() as u64).to_be_bytes())
+            .chain_update(&encrypted.data)
+            .chain_update(user_public_key.as_bytes())
+            .finalize()
+            .into();
+        Self { commitment, epoch }
+    }
+}
+
+struct Entry {
+    proof: String,
+    inserted: Instant,
+    generation: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<ProofKey, Entry>,
+    /// Live keys by the generation they were inserted at, oldest first.
+    order: BTreeMap<u64, ProofKey>,
+    bytes: usize,
+    generation: u64,
+}
+
+/// Memory an entry holds, counting its slot in `order`.
+fn cost(proof: &str) -> usize {
+    proof.len()
+        + mem::size_of::<ProofKey>()
+        + mem::size_of::<Entry>()
+        + mem::size_of::<(u64, ProofKey)>()
+}
+
+impl Inner {
+    fn remove(&mut self, key: &ProofKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.generation);
+            self.bytes -= cost(&entry.proof);
+        }
+    }
+
+    fn evict(&mut self, ttl: Duration, max_bytes: usize) {
+        while let Some((_, &key)) = self.order.first_key_value() {
+            let entry = &self.entries[&key];
+            if self.bytes <= max_bytes && entry.inserted.elapsed() < ttl {
+                break;
+            }
+            self.remove(&key);
+        }
+    }
+}
+
+pub struct ProofCache {
+    ttl: Duration,
+    max_bytes: usize,
+    inner: Mutex<Inner>,
+}
+
+impl ProofCache {
+    pub fn new(ttl: Duration, max_bytes: usize) -> Self {
+        Self {
+            ttl,
+            max_bytes,
+            inner: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, key: &ProofKey) -> Option<String> {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner.entries.get(key)?;
+        if entry.inserted.elapsed() >= self.ttl {
+            inner.remove(key);
+            return None;
+        }
+        Some(entry.proof.clone())
+    }
+
+    /// Proofs larger than the whole budget are not cached.
+    pub fn insert(&self, key: ProofKey, proof: String) {
+        if cost(&proof) > self.max_bytes {
+            return;
+        }
+        let mut inner = self.inner.lock().unwrap();
+        inner.remove(&key);
+        inner.generation += 1;
+        let generation = inner.generation;
+        inner.bytes += cost(&proof);
+        inner.entries.insert(
+            key,
+            Entry {
+                proof,
+                inserted: Instant::now(),
+                generation,
+            },
+        );
+        inner.order.insert(generation, key);
+        inner.evict(self.ttl, self.max_bytes);
+    }
+
+    /// Returns the cached proof, or generates and caches one.
+    ///
+    /// `generate` runs without the cache locked, so concurrent misses on the
+    /// same key may each generate a proof.
+    pub fn get_or_generate(
+        &self,
+        key: ProofKey,
+        generate: impl FnOnce() -> Result<String, Status>,
+    ) -> Result<String, Status> {
+        if let Some(proof) = self.get(&key) {
+            return Ok(proof);
+        }
+        let proof = generate()?;
+        self.insert(key, proof.clone());
+        Ok(proof)
+    }
+
+    /// Approximate memory held by cached proofs and their bookkeeping, in
+    /// bytes.
+    pub fn size(&self) -> usize {
+        self.inner.lock().unwrap().bytes
+    }
+}
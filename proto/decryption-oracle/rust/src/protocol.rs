@@ -0,0 +1,353 @@
+//! Transport-agnostic oracle client protocol.
+//!
+//! [`Protocol`] builds requests, attaches the caller's proof and verifies the
+//! oracle's signature on responses, without doing any I/O itself. Callers send
+//! the prepared request over whatever transport they have and hand the
+//! response back together with the request it answers. The tonic transport
+//! lives in [`crate::client`]; this module only needs `prost` messages, so it
+//! builds without the `client`/`server` features.
+//!
+//! Both the proof and the oracle signature are computed over the canonical
+//! payloads returned by the `*_payload` functions, which oracle
+//! implementations use to produce their signatures.
+
+use std::fmt;
+
+use crate::oracle::{
+    AggregateOp, AggregateRequest, AggregateResponse, BucketRequest, BucketResponse,
+    DecryptRequest, DecryptResponse, FheEncrypted, IsNilRequest, IsNilResponse, ReencryptRequest,
+    ReencryptResponse,
+};
+use crate::request::RequestError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProtocolError {
+    /// The request would be rejected by the oracle.
+    Invalid(RequestError),
+    /// The caller's signer failed to produce a proof.
+    Proof(String),
+    /// The oracle's signature does not match the response.
+    BadSignature,
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::Invalid(err) => write!(f, "invalid request: {err}"),
+            ProtocolError::Proof(err) => write!(f, "failed to produce proof: {err}"),
+            ProtocolError::BadSignature => write!(f, "oracle signature does not verify"),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl From<RequestError> for ProtocolError {
+    fn from(err: RequestError) -> Self {
+        ProtocolError::Invalid(err)
+    }
+}
+
+/// Produces the proof attached to a request from its canonical payload.
+pub trait ProofSigner {
+    fn sign(&self, payload: &[u8]) -> Result<String, ProtocolError>;
+}
+
+impl<F> ProofSigner for F
+where
+    F: Fn(&[u8]) -> Result<String, ProtocolError>,
+{
+    fn sign(&self, payload: &[u8]) -> Result<String, ProtocolError> {
+        self(payload)
+    }
+}
+
+/// Leaves the proof field empty, for oracles that do not check it yet.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProof;
+
+impl ProofSigner for NoProof {
+    fn sign(&self, _payload: &[u8]) -> Result<String, ProtocolError> {
+        Ok(String::new())
+    }
+}
+
+/// Checks an oracle signature over a canonical response payload.
+pub trait SignatureVerifier {
+    fn verify(&self, payload: &[u8], signature: &str) -> bool;
+}
+
+impl<F> SignatureVerifier for F
+where
+    F: Fn(&[u8], &str) -> bool,
+{
+    fn verify(&self, payload: &[u8], signature: &str) -> bool {
+        self(payload, signature)
+    }
+}
+
+struct Payload(Vec<u8>);
+
+impl Payload {
+    fn new(domain: &str) -> Self {
+        let mut payload = Payload(Vec::new());
+        payload.bytes(domain.as_bytes());
+        payload
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.0
+            .extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    fn int(&mut self, value: i32) -> &mut Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    fn encrypted(&mut self, encrypted: &FheEncrypted) -> &mut Self {
+        self.int(encrypted.r#type).bytes(&encrypted.data)
+    }
+
+    fn all(&mut self, encrypted: &[FheEncrypted]) -> &mut Self {
+        self.0
+            .extend_from_slice(&(encrypted.len() as u64).to_be_bytes());
+        for encrypted in encrypted {
+            self.encrypted(encrypted);
+        }
+        self
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.0)
+    }
+}
+
+fn data(encrypted: &Option<FheEncrypted>) -> &[u8] {
+    encrypted.as_ref().map_or(&[], |encrypted| &encrypted.data)
+}
+
+fn type_tag(encrypted: &Option<FheEncrypted>) -> i32 {
+    encrypted.as_ref().map_or(-1, |encrypted| encrypted.r#type)
+}
+
+pub fn decrypt_payload(request: &DecryptRequest) -> Vec<u8> {
+    Payload::new("oracle.DecryptRequest")
+        .int(type_tag(&request.encrypted))
+        .bytes(data(&request.encrypted))
+        .finish()
+}
+
+pub fn decrypt_response_payload(request: &DecryptRequest, response: &DecryptResponse) -> Vec<u8> {
+    Payload::new("oracle.DecryptResponse")
+        .bytes(&decrypt_payload(request))
+        .bytes(response.decrypted.as_bytes())
+        .finish()
+}
+
+pub fn reencrypt_payload(request: &ReencryptRequest) -> Vec<u8> {
+    Payload::new("oracle.ReencryptRequest")
+        .int(type_tag(&request.encrypted))
+        .bytes(data(&request.encrypted))
+        .bytes(request.user_public_key.as_bytes())
+        .finish()
+}
+
+pub fn reencrypt_response_payload(
+    request: &ReencryptRequest,
+    response: &ReencryptResponse,
+) -> Vec<u8> {
+    Payload::new("oracle.ReencryptResponse")
+        .bytes(&reencrypt_payload(request))
+        .bytes(response.reencrypted.as_bytes())
+        .finish()
+}
+
+pub fn is_nil_payload(request: &IsNilRequest) -> Vec<u8> {
+    Payload::new("oracle.IsNilRequest")
+        .int(type_tag(&request.encrypted))
+        .bytes(data(&request.encrypted))
+        .finish()
+}
+
+pub fn is_nil_response_payload(request: &IsNilRequest, response: &IsNilResponse) -> Vec<u8> {
+    Payload::new("oracle.IsNilResponse")
+        .bytes(&is_nil_payload(request))
+        .int(i32::from(response.is_nil))
+        .finish()
+}
+
+pub fn aggregate_payload(request: &AggregateRequest) -> Vec<u8> {
+    Payload::new("oracle.AggregateRequest")
+        .all(&request.encrypted)
+        .int(request.op)
+        .bytes(request.threshold.as_bytes())
+        .finish()
+}
+
+pub fn aggregate_response_payload(
+    request: &AggregateRequest,
+    response: &AggregateResponse,
+) -> Vec<u8> {
+    Payload::new("oracle.AggregateResponse")
+        .bytes(&aggregate_payload(request))
+        .bytes(response.aggregate.as_bytes())
+        .finish()
+}
+
+pub fn bucket_payload(request: &BucketRequest) -> Vec<u8> {
+    Payload::new("oracle.BucketRequest")
+        .int(type_tag(&request.encrypted))
+        .bytes(data(&request.encrypted))
+        .bytes(request.bucket_set.as_bytes())
+        .finish()
+}
+
+pub fn bucket_response_payload(request: &BucketRequest, response: &BucketResponse) -> Vec<u8> {
+    Payload::new("oracle.BucketResponse")
+        .bytes(&bucket_payload(request))
+        .bytes(&response.bucket.to_be_bytes())
+        .finish()
+}
+
+/// Request building and response verification for one oracle.
+#[derive(Clone, Debug)]
+pub struct Protocol<S, V> {
+    signer: S,
+    verifier: V,
+}
+
+impl<S: ProofSigner, V: SignatureVerifier> Protocol<S, V> {
+    pub fn new(signer: S, verifier: V) -> Self {
+        Self { signer, verifier }
+    }
+
+    fn verified(&self, payload: &[u8], signature: &str) -> Result<(), ProtocolError> {
+        if self.verifier.verify(payload, signature) {
+            Ok(())
+        } else {
+            Err(ProtocolError::BadSignature)
+        }
+    }
+
+    pub fn decrypt(&self, encrypted: FheEncrypted) -> Result<DecryptRequest, ProtocolError> {
+        let mut request = DecryptRequest::new(encrypted);
+        request.validate()?;
+        request.proof = self.signer.sign(&decrypt_payload(&request))?;
+        Ok(request)
+    }
+
+    /// Returns the decrypted value once the oracle signature checks out.
+    pub fn verify_decrypt(
+        &self,
+        request: &DecryptRequest,
+        response: DecryptResponse,
+    ) -> Result<String, ProtocolError> {
+        self.verified(
+            &decrypt_response_payload(request, &response),
+            &response.signature,
+        )?;
+        Ok(response.decrypted)
+    }
+
+    pub fn reencrypt(
+        &self,
+        encrypted: FheEncrypted,
+        user_public_key: impl Into<String>,
+    ) -> Result<ReencryptRequest, ProtocolError> {
+        let mut request = ReencryptRequest::builder()
+            .encrypted(encrypted)
+            .user_key(user_public_key)
+            .build()?;
+        request.proof = self.signer.sign(&reencrypt_payload(&request))?;
+        Ok(request)
+    }
+
+    pub fn verify_reencrypt(
+        &self,
+        request: &ReencryptRequest,
+        response: ReencryptResponse,
+    ) -> Result<String, ProtocolError> {
+        self.verified(
+            &reencrypt_response_payload(request, &response),
+            &response.signature,
+        )?;
+        Ok(response.reencrypted)
+    }
+
+    pub fn is_nil(&self, encrypted: FheEncrypted) -> Result<IsNilRequest, ProtocolError> {
+        let mut request = IsNilRequest::new(encrypted);
+        request.validate()?;
+        request.proof = self.signer.sign(&is_nil_payload(&request))?;
+        Ok(request)
+    }
+
+    pub fn verify_is_nil(
+        &self,
+        request: &IsNilRequest,
+        response: IsNilResponse,
+    ) -> Result<bool, ProtocolError> {
+        self.verified(
+            &is_nil_response_payload(request, &response),
+            &response.signature,
+        )?;
+        Ok(response.is_nil)
+    }
+
+    /// `threshold` is only used by [`AggregateOp::CountAbove`] and ignored
+    /// otherwise.
+    pub fn aggregate(
+        &self,
+        encrypted: Vec<FheEncrypted>,
+        op: AggregateOp,
+        threshold: impl Into<String>,
+    ) -> Result<AggregateRequest, ProtocolError> {
+        let mut request = match op {
+            AggregateOp::Sum => AggregateRequest::sum(encrypted),
+            AggregateOp::Mean => AggregateRequest::mean(encrypted),
+            AggregateOp::CountAbove => AggregateRequest::count_above(encrypted, threshold),
+        };
+        request.validate()?;
+        request.proof = self.signer.sign(&aggregate_payload(&request))?;
+        Ok(request)
+    }
+
+    pub fn verify_aggregate(
+        &self,
+        request: &AggregateRequest,
+        response: AggregateResponse,
+    ) -> Result<String, ProtocolError> {
+        self.verified(
+            &aggregate_response_payload(request, &response),
+            &response.signature,
+        )?;
+        Ok(response.aggregate)
+    }
+
+    /// `bucket_set` names a set of bounds the oracle has configured.
+    pub fn bucket(
+        &self,
+        encrypted: FheEncrypted,
+        bucket_set: impl Into<String>,
+    ) -> Result<BucketRequest, ProtocolError> {
+        let mut request = BucketRequest::new(encrypted, bucket_set);
+        request.validate()?;
+        request.proof = self.signer.sign(&bucket_payload(&request))?;
+        Ok(request)
+    }
+
+    /// Returns the number of bounds in the set the value is at or above.
+    pub fn verify_bucket(
+        &self,
+        request: &BucketRequest,
+        response: BucketResponse,
+    ) -> Result<u32, ProtocolError> {
+        self.verified(
+            &bucket_response_payload(request, &response),
+            &response.signature,
+        )?;
+        Ok(response.bucket)
+    }
+}
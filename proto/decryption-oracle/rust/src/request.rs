@@ -0,0 +1,296 @@
+//! Typed constructors and validation for oracle requests.
+//!
+//! The generated messages accept any `i32` as a type tag and leave required
+//! fields optional. Requests built here always carry a known
+//! [`EncryptedType`] and every required field; servers run
+//! [`validate`](DecryptRequest::validate) on what they receive.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::oracle::{
+    AggregateOp, AggregateRequest, BucketRequest, DecryptRequest, EncryptedType, FheEncrypted,
+    IsNilRequest, ReencryptRequest,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequestError {
+    MissingEncrypted,
+    EmptyCiphertext,
+    /// The type tag is not an [`EncryptedType`].
+    UnknownType(i32),
+    MissingUserKey,
+    /// The op tag is not an [`AggregateOp`].
+    UnknownOp(i32),
+    /// An aggregate needs at least this many values.
+    TooFewValues(usize),
+    /// An aggregate mixes encrypted types.
+    MixedTypes,
+    /// An aggregate names the same ciphertext more than once.
+    DuplicateCiphertext,
+    /// CountAbove needs a decimal threshold.
+    BadThreshold,
+    MissingBucketSet,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::MissingEncrypted => write!(f, "encrypted value is required"),
+            RequestError::EmptyCiphertext => write!(f, "encrypted value has no data"),
+            RequestError::UnknownType(tag) => write!(f, "unknown encrypted type {tag}"),
+            RequestError::MissingUserKey => write!(f, "user public key is required"),
+            RequestError::UnknownOp(tag) => write!(f, "unknown aggregate op {tag}"),
+            RequestError::TooFewValues(min) => {
+                write!(f, "an aggregate needs at least {min} values")
+            }
+            RequestError::MixedTypes => write!(f, "aggregated values must share one type"),
+            RequestError::DuplicateCiphertext => {
+                write!(f, "aggregated values must be distinct ciphertexts")
+            }
+            RequestError::BadThreshold => write!(f, "threshold must be a decimal number"),
+            RequestError::MissingBucketSet => write!(f, "bucket set is required"),
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
+
+#[cfg(any(feature = "client", feature = "server"))]
+impl From<RequestError> for tonic::Status {
+    fn from(err: RequestError) -> Self {
+        tonic::Status::invalid_argument(err.to_string())
+    }
+}
+
+impl FheEncrypted {
+    pub fn new(data: impl Into<Vec<u8>>, ty: EncryptedType) -> Self {
+        Self {
+            data: data.into(),
+            r#type: ty.into(),
+        }
+    }
+
+    /// Unlike the generated `r#type()`, fails on unknown tags instead of
+    /// falling back to `Uint8`.
+    pub fn encrypted_type(&self) -> Result<EncryptedType, RequestError> {
+        EncryptedType::try_from(self.r#type).map_err(|_| RequestError::UnknownType(self.r#type))
+    }
+
+    pub fn validate(&self) -> Result<(), RequestError> {
+        self.encrypted_type()?;
+        if self.data.is_empty() {
+            return Err(RequestError::EmptyCiphertext);
+        }
+        Ok(())
+    }
+}
+
+fn is_decimal(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The smallest set an aggregate may be computed over. Oracles may configure
+/// a larger one with [`AggregateRequest::validate_min_size`].
+///
+/// This and the duplicate check only reject malformed input. They do not
+/// protect individual values: padding a set with known values still reveals
+/// the rest.
+pub const MIN_AGGREGATE_SIZE: usize = 2;
+
+fn validate_encrypted(encrypted: &Option<FheEncrypted>) -> Result<(), RequestError> {
+    encrypted
+        .as_ref()
+        .ok_or(RequestError::MissingEncrypted)?
+        .validate()
+}
+
+macro_rules! single_ciphertext_request {
+    ($request:ident) => {
+        impl $request {
+            pub fn new(encrypted: FheEncrypted) -> Self {
+                Self {
+                    encrypted: Some(encrypted),
+                    proof: String::new(),
+                }
+            }
+
+            pub fn for_uint8(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint8))
+            }
+
+            pub fn for_uint16(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint16))
+            }
+
+            pub fn for_uint32(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint32))
+            }
+
+            pub fn for_uint64(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint64))
+            }
+
+            pub fn for_uint128(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint128))
+            }
+
+            pub fn for_uint256(data: impl Into<Vec<u8>>) -> Self {
+                Self::new(FheEncrypted::new(data, EncryptedType::Uint256))
+            }
+
+            #[must_use]
+            pub fn with_proof(mut self, proof: impl Into<String>) -> Self {
+                self.proof = proof.into();
+                self
+            }
+
+            pub fn validate(&self) -> Result<(), RequestError> {
+                validate_encrypted(&self.encrypted)
+            }
+        }
+    };
+}
+
+single_ciphertext_request!(DecryptRequest);
+single_ciphertext_request!(IsNilRequest);
+
+impl ReencryptRequest {
+    pub fn builder() -> ReencryptRequestBuilder {
+        ReencryptRequestBuilder::default()
+    }
+
+    pub fn validate(&self) -> Result<(), RequestError> {
+        validate_encrypted(&self.encrypted)?;
+        if self.user_public_key.is_empty() {
+            return Err(RequestError::MissingUserKey);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReencryptRequestBuilder {
+    encrypted: Option<FheEncrypted>,
+    user_public_key: String,
+    proof: String,
+}
+
+impl ReencryptRequestBuilder {
+    #[must_use]
+    pub fn encrypted(mut self, encrypted: FheEncrypted) -> Self {
+        self.encrypted = Some(encrypted);
+        self
+    }
+
+    /// Hex encoded public key of the user the value is reencrypted to.
+    #[must_use]
+    pub fn user_key(mut self, user_public_key: impl Into<String>) -> Self {
+        self.user_public_key = user_public_key.into();
+        self
+    }
+
+    #[must_use]
+    pub fn proof(mut self, proof: impl Into<String>) -> Self {
+        self.proof = proof.into();
+        self
+    }
+
+    pub fn build(self) -> Result<ReencryptRequest, RequestError> {
+        let request = ReencryptRequest {
+            encrypted: self.encrypted,
+            user_public_key: self.user_public_key,
+            proof: self.proof,
+        };
+        request.validate()?;
+        Ok(request)
+    }
+}
+
+impl AggregateRequest {
+    fn new(encrypted: Vec<FheEncrypted>, op: AggregateOp, threshold: String) -> Self {
+        Self {
+            encrypted,
+            op: op.into(),
+            threshold,
+            proof: String::new(),
+        }
+    }
+
+    pub fn sum(encrypted: Vec<FheEncrypted>) -> Self {
+        Self::new(encrypted, AggregateOp::Sum, String::new())
+    }
+
+    pub fn mean(encrypted: Vec<FheEncrypted>) -> Self {
+        Self::new(encrypted, AggregateOp::Mean, String::new())
+    }
+
+    /// `threshold` is a decimal plaintext.
+    pub fn count_above(encrypted: Vec<FheEncrypted>, threshold: impl Into<String>) -> Self {
+        Self::new(encrypted, AggregateOp::CountAbove, threshold.into())
+    }
+
+    #[must_use]
+    pub fn with_proof(mut self, proof: impl Into<String>) -> Self {
+        self.proof = proof.into();
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), RequestError> {
+        self.validate_min_size(MIN_AGGREGATE_SIZE)
+    }
+
+    /// Like [`validate`](Self::validate), but requires at least `min_size`
+    /// values; sizes below [`MIN_AGGREGATE_SIZE`] are raised to it.
+    pub fn validate_min_size(&self, min_size: usize) -> Result<(), RequestError> {
+        if self.encrypted.is_empty() {
+            return Err(RequestError::MissingEncrypted);
+        }
+        self.encrypted.iter().try_for_each(FheEncrypted::validate)?;
+        let min_size = min_size.max(MIN_AGGREGATE_SIZE);
+        if self.encrypted.len() < min_size {
+            return Err(RequestError::TooFewValues(min_size));
+        }
+        if self
+            .encrypted
+            .iter()
+            .any(|e| e.r#type != self.encrypted[0].r#type)
+        {
+            return Err(RequestError::MixedTypes);
+        }
+        let mut seen = HashSet::new();
+        if !self.encrypted.iter().all(|e| seen.insert(&e.data[..])) {
+            return Err(RequestError::DuplicateCiphertext);
+        }
+        let op = AggregateOp::try_from(self.op).map_err(|_| RequestError::UnknownOp(self.op))?;
+        if op == AggregateOp::CountAbove && !is_decimal(&self.threshold) {
+            return Err(RequestError::BadThreshold);
+        }
+        Ok(())
+    }
+}
+
+impl BucketRequest {
+    /// `bucket_set` names a set of bounds the oracle has configured.
+    pub fn new(encrypted: FheEncrypted, bucket_set: impl Into<String>) -> Self {
+        Self {
+            encrypted: Some(encrypted),
+            bucket_set: bucket_set.into(),
+            proof: String::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_proof(mut self, proof: impl Into<String>) -> Self {
+        self.proof = proof.into();
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), RequestError> {
+        validate_encrypted(&self.encrypted)?;
+        if self.bucket_set.is_empty() {
+            return Err(RequestError::MissingBucketSet);
+        }
+        Ok(())
+    }
+}
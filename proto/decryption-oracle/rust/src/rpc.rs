@@ -0,0 +1,36 @@
+//! Names of the oracle RPCs, shared by tenant configuration and transcripts.
+
+/// An oracle RPC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Rpc {
+    Decrypt,
+    Reencrypt,
+    AssertIsNil,
+    Aggregate,
+    Bucket,
+}
+
+impl Rpc {
+    pub const ALL: [Rpc; 5] = [
+        Rpc::Decrypt,
+        Rpc::Reencrypt,
+        Rpc::AssertIsNil,
+        Rpc::Aggregate,
+        Rpc::Bucket,
+    ];
+
+    /// The gRPC method name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rpc::Decrypt => "Decrypt",
+            Rpc::Reencrypt => "Reencrypt",
+            Rpc::AssertIsNil => "AssertIsNil",
+            Rpc::Aggregate => "Aggregate",
+            Rpc::Bucket => "Bucket",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Rpc::ALL.into_iter().find(|rpc| rpc.name() == name)
+    }
+}
@@ -0,0 +1,299 @@
+//! Tenant registry for serving several applications from one oracle.
+//!
+//! Each tenant gets its own key, rate limit, set of allowed RPCs and fee
+//! schedule. Callers are mapped to tenants by an [`Authenticator`], such as
+//! [`ApiKeys`], never by a name they merely assert. Handlers call
+//! [`TenantRegistry::admit`] before doing any work; the credentials and the
+//! whole configuration can be swapped with [`TenantRegistry::reload`] while
+//! the server is running.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Instant;
+
+use sha2::{Digest, Sha256};
+use tonic::metadata::MetadataMap;
+use tonic::{Extensions, Request, Status};
+
+use crate::metadata;
+use crate::rpc::Rpc;
+
+/// Establishes which tenant a call is made by.
+///
+/// Implementations must derive the tenant from something the caller proves,
+/// such as an API key checked by the server or a TLS client certificate found
+/// in the request extensions.
+pub trait Authenticator: Send + Sync {
+    fn authenticate(
+        &self,
+        metadata: &MetadataMap,
+        extensions: &Extensions,
+    ) -> Result<String, Status>;
+}
+
+/// Authenticates callers by the API key in their `x-api-key` metadata. Only
+/// SHA-256 digests of the keys are kept.
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeys {
+    tenants: HashMap<[u8; 32], String>,
+}
+
+impl ApiKeys {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with(mut self, api_key: &str, tenant: impl Into<String>) -> Self {
+        self.tenants
+            .insert(Sha256::digest(api_key).into(), tenant.into());
+        self
+    }
+}
+
+impl Authenticator for ApiKeys {
+    fn authenticate(
+        &self,
+        metadata: &MetadataMap,
+        _extensions: &Extensions,
+    ) -> Result<String, Status> {
+        let api_key = metadata
+            .get(metadata::API_KEY)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| {
+                Status::unauthenticated(format!("missing {} metadata", metadata::API_KEY))
+            })?;
+        let digest: [u8; 32] = Sha256::digest(api_key).into();
+        self.tenants
+            .get(&digest)
+            .cloned()
+            .ok_or_else(|| Status::unauthenticated("unknown API key"))
+    }
+}
+
+/// Token bucket refilled at `per_second`, holding at most `burst` calls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    per_second: f64,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Returns `None` unless `per_second` is finite and not negative and
+    /// `burst` is at least 1.
+    pub fn new(per_second: f64, burst: u32) -> Option<Self> {
+        (per_second.is_finite() && per_second >= 0.0 && burst > 0)
+            .then_some(Self { per_second, burst })
+    }
+
+    pub fn per_second(&self) -> f64 {
+        self.per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst
+    }
+}
+
+/// Fee charged per call, in the deployment's fee unit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    default: u64,
+    per_rpc: HashMap<Rpc, u64>,
+}
+
+impl FeeSchedule {
+    pub fn flat(fee: u64) -> Self {
+        Self {
+            default: fee,
+            per_rpc: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with(mut self, rpc: Rpc, fee: u64) -> Self {
+        self.per_rpc.insert(rpc, fee);
+        self
+    }
+
+    pub fn fee(&self, rpc: Rpc) -> u64 {
+        self.per_rpc.get(&rpc).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TenantConfig {
+    /// Identifier of the key set used for this tenant's ciphertexts.
+    pub key_id: String,
+    /// `None` leaves the tenant unthrottled.
+    pub rate_limit: Option<RateLimit>,
+    pub allowed_rpcs: HashSet<Rpc>,
+    pub fees: FeeSchedule,
+}
+
+impl TenantConfig {
+    /// A config allowing every RPC, without rate limit or fees.
+    pub fn new(key_id: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            rate_limit: None,
+            allowed_rpcs: Rpc::ALL.into_iter().collect(),
+            fees: FeeSchedule::default(),
+        }
+    }
+}
+
+/// What a handler needs to know about an admitted call.
+#[derive(Clone, Debug)]
+pub struct Admission {
+    pub tenant: String,
+    pub config: Arc<TenantConfig>,
+    pub fee: u64,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Bucket {
+    fn full(limit: &RateLimit) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn take(&mut self, limit: &RateLimit) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(f64::from(limit.burst));
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct Tenant {
+    config: Arc<TenantConfig>,
+    bucket: Mutex<Option<Bucket>>,
+}
+
+impl Tenant {
+    fn new(config: TenantConfig) -> Self {
+        let bucket = config.rate_limit.as_ref().map(Bucket::full);
+        Self {
+            config: Arc::new(config),
+            bucket: Mutex::new(bucket),
+        }
+    }
+}
+
+struct Tenants {
+    authenticator: Box<dyn Authenticator>,
+    by_id: HashMap<String, Arc<Tenant>>,
+}
+
+impl Tenants {
+    fn reload(&mut self, configs: impl IntoIterator<Item = (String, TenantConfig)>) {
+        let mut reloaded = HashMap::new();
+        for (id, config) in configs {
+            let tenant = match self.by_id.remove(&id) {
+                Some(old) if old.config.rate_limit == config.rate_limit => {
+                    let bucket = old.bucket.lock().unwrap().take();
+                    Tenant {
+                        config: Arc::new(config),
+                        bucket: Mutex::new(bucket),
+                    }
+                }
+                _ => Tenant::new(config),
+            };
+            reloaded.insert(id, Arc::new(tenant));
+        }
+        self.by_id = reloaded;
+    }
+}
+
+pub struct TenantRegistry {
+    tenants: RwLock<Tenants>,
+}
+
+impl TenantRegistry {
+    pub fn new(
+        authenticator: impl Authenticator + 'static,
+        configs: impl IntoIterator<Item = (String, TenantConfig)>,
+    ) -> Self {
+        let mut tenants = Tenants {
+            authenticator: Box::new(authenticator),
+            by_id: HashMap::new(),
+        };
+        tenants.reload(configs);
+        Self {
+            tenants: RwLock::new(tenants),
+        }
+    }
+
+    /// Replaces the credentials and the whole configuration at once, so API
+    /// keys can be rotated and tenants added without a restart.
+    ///
+    /// Tenants whose rate limit is unchanged keep their current bucket, so a
+    /// reload cannot be used to reset a throttled tenant's quota.
+    pub fn reload(
+        &self,
+        authenticator: impl Authenticator + 'static,
+        configs: impl IntoIterator<Item = (String, TenantConfig)>,
+    ) {
+        let mut tenants = self.tenants.write().unwrap();
+        tenants.authenticator = Box::new(authenticator);
+        tenants.reload(configs);
+    }
+
+    pub fn get(&self, tenant: &str) -> Option<Arc<TenantConfig>> {
+        let tenants = self.tenants.read().unwrap();
+        tenants
+            .by_id
+            .get(tenant)
+            .map(|tenant| tenant.config.clone())
+    }
+
+    /// Checks that the caller authenticates as a configured tenant that may
+    /// call `rpc` and is within its rate limit, consuming one unit of quota on
+    /// success.
+    pub fn admit<T>(&self, request: &Request<T>, rpc: Rpc) -> Result<Admission, Status> {
+        let (id, tenant) = {
+            let tenants = self.tenants.read().unwrap();
+            let id = tenants
+                .authenticator
+                .authenticate(request.metadata(), request.extensions())?;
+            let tenant = tenants.by_id.get(&id).cloned();
+            (id, tenant)
+        };
+        let tenant =
+            tenant.ok_or_else(|| Status::permission_denied(format!("unknown tenant {id}")))?;
+
+        if !tenant.config.allowed_rpcs.contains(&rpc) {
+            return Err(Status::permission_denied(format!(
+                "tenant {id} may not call {rpc:?}"
+            )));
+        }
+        if let Some(limit) = &tenant.config.rate_limit {
+            let mut bucket = tenant.bucket.lock().unwrap();
+            let bucket = bucket.get_or_insert_with(|| Bucket::full(limit));
+            if !bucket.take(limit) {
+                return Err(Status::resource_exhausted(format!(
+                    "tenant {id} exceeded its rate limit"
+                )));
+            }
+        }
+
+        Ok(Admission {
+            tenant: id,
+            config: tenant.config.clone(),
+            fee: tenant.config.fees.fee(rpc),
+        })
+    }
+}
@@ -0,0 +1,454 @@
+//! Golden transcripts of oracle exchanges.
+//!
+//! [`Recorder`] wraps an oracle and records every successful exchange: the
+//! encoded request and response, the payload the response signature covers,
+//! and which credentials the call carried. A [`Transcript`] is saved as
+//! versioned text and later replayed against a new server build with
+//! [`Transcript::replay`], which reports the first exchange whose wire
+//! encoding, signing payload or response changed.
+//!
+//! Credential values are never recorded, only their metadata keys; replaying
+//! against an oracle that checks them takes fresh values from
+//! [`Transcript::replay_with`].
+//!
+//! The text form starts with an `oracle-transcript <version>` line followed by
+//! one exchange per line: the RPC name, the credential keys joined by `,` or
+//! `-` for none, then the hex encoded request, response and signing payload.
+//! Blank lines and lines starting with `#` are ignored.
+//!
+//! Version 2 added Bucket exchanges. Version 1 transcripts still parse and
+//! replay, but may not contain them.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use prost::Message;
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status};
+
+use crate::metadata::{API_KEY, DELEGATION_TOKEN};
+use crate::oracle::decryption_oracle_server::DecryptionOracle;
+use crate::oracle::{
+    AggregateRequest, AggregateResponse, BucketRequest, BucketResponse, DecryptRequest,
+    DecryptResponse, IsNilRequest, IsNilResponse, ReencryptRequest, ReencryptResponse,
+};
+use crate::protocol::{
+    aggregate_response_payload, bucket_response_payload, decrypt_response_payload,
+    is_nil_response_payload, reencrypt_response_payload,
+};
+use crate::rpc::Rpc;
+
+/// Transcript format version written by [`Transcript::to_text`].
+pub const VERSION: u32 = 2;
+
+/// Oldest transcript format version [`Transcript::parse`] accepts.
+pub const OLDEST_VERSION: u32 = 1;
+
+const HEADER: &str = "oracle-transcript";
+
+/// Metadata keys recorded as credentials, without their values.
+const CREDENTIALS: [&str; 2] = [API_KEY, DELEGATION_TOKEN];
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    UnsupportedVersion(String),
+    /// The text form is malformed at this (1-based) line.
+    Malformed(usize),
+    /// A recorded message no longer decodes.
+    Decode {
+        exchange: usize,
+    },
+    /// No valid value was supplied for a recorded credential.
+    MissingCredential {
+        exchange: usize,
+        key: &'static str,
+    },
+    /// The replayed oracle failed a recorded exchange.
+    Status {
+        exchange: usize,
+        status: Status,
+    },
+    Mismatch {
+        exchange: usize,
+        what: &'static str,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::UnsupportedVersion(version) => {
+                write!(f, "unsupported transcript version {version}")
+            }
+            TranscriptError::Malformed(line) => write!(f, "malformed transcript at line {line}"),
+            TranscriptError::Decode { exchange } => {
+                write!(f, "exchange {exchange} no longer decodes")
+            }
+            TranscriptError::MissingCredential { exchange, key } => {
+                write!(f, "exchange {exchange} needs a value for {key}")
+            }
+            TranscriptError::Status { exchange, status } => {
+                write!(f, "exchange {exchange} failed: {status}")
+            }
+            TranscriptError::Mismatch { exchange, what } => {
+                write!(f, "exchange {exchange}: {what} differs from the recording")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+/// The first transcript version that can record `rpc`.
+fn introduced_in(rpc: Rpc) -> u32 {
+    match rpc {
+        Rpc::Bucket => 2,
+        _ => 1,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exchange {
+    pub rpc: Rpc,
+    /// Metadata keys of the credentials the call carried.
+    pub credentials: Vec<&'static str>,
+    pub request: Vec<u8>,
+    pub response: Vec<u8>,
+    /// The payload the response signature was computed over.
+    pub payload: Vec<u8>,
+}
+
+impl Exchange {
+    fn new(
+        rpc: Rpc,
+        metadata: &MetadataMap,
+        request: &impl Message,
+        response: &impl Message,
+        payload: Vec<u8>,
+    ) -> Self {
+        let credentials = CREDENTIALS
+            .into_iter()
+            .filter(|key| metadata.contains_key(*key))
+            .collect();
+        Self {
+            rpc,
+            credentials,
+            request: request.encode_to_vec(),
+            response: response.encode_to_vec(),
+            payload,
+        }
+    }
+
+    fn decode<Req, Resp>(&self, exchange: usize) -> Result<(Req, Resp), TranscriptError>
+    where
+        Req: Message + Default,
+        Resp: Message + Default,
+    {
+        let request = Req::decode(&self.request[..]);
+        let response = Resp::decode(&self.response[..]);
+        match (request, response) {
+            (Ok(request), Ok(response)) => Ok((request, response)),
+            _ => Err(TranscriptError::Decode { exchange }),
+        }
+    }
+
+    /// Wraps `message` with a value from `credentials` for every recorded
+    /// credential.
+    fn request<M>(
+        &self,
+        exchange: usize,
+        message: M,
+        credentials: &impl Fn(usize, &str) -> Option<String>,
+    ) -> Result<Request<M>, TranscriptError> {
+        let mut request = Request::new(message);
+        for &key in &self.credentials {
+            let value = credentials(exchange, key)
+                .and_then(|value| value.parse().ok())
+                .ok_or(TranscriptError::MissingCredential { exchange, key })?;
+            request.metadata_mut().insert(key, value);
+        }
+        Ok(request)
+    }
+
+    /// Checks the recording against the current encoding and signing payload,
+    /// then against the response the oracle gave on replay.
+    fn check(
+        &self,
+        exchange: usize,
+        recorded: (&impl Message, &impl Message),
+        payload: Vec<u8>,
+        replayed: &impl Message,
+    ) -> Result<(), TranscriptError> {
+        let mismatch = |what| Err(TranscriptError::Mismatch { exchange, what });
+        if recorded.0.encode_to_vec() != self.request {
+            return mismatch("request encoding");
+        }
+        if recorded.1.encode_to_vec() != self.response {
+            return mismatch("response encoding");
+        }
+        if payload != self.payload {
+            return mismatch("signing payload");
+        }
+        if replayed.encode_to_vec() != self.response {
+            return mismatch("response");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub exchanges: Vec<Exchange>,
+}
+
+impl Transcript {
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{HEADER} {VERSION}\n");
+        for exchange in &self.exchanges {
+            let credentials = match exchange.credentials.join(",") {
+                joined if joined.is_empty() => "-".to_owned(),
+                joined => joined,
+            };
+            text.push_str(&format!(
+                "{} {credentials} {} {} {}\n",
+                exchange.rpc.name(),
+                hex::encode(&exchange.request),
+                hex::encode(&exchange.response),
+                hex::encode(&exchange.payload),
+            ));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TranscriptError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let version = match lines.next() {
+            Some((_, line)) if line.starts_with(HEADER) => {
+                let version = line[HEADER.len()..].trim();
+                version
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|version| (OLDEST_VERSION..=VERSION).contains(version))
+                    .ok_or_else(|| TranscriptError::UnsupportedVersion(version.to_string()))?
+            }
+            Some((number, _)) => return Err(TranscriptError::Malformed(number)),
+            None => return Err(TranscriptError::Malformed(1)),
+        };
+
+        let exchanges = lines
+            .map(|(number, line)| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [rpc, credentials, request, response, payload] = fields[..] else {
+                    return Err(TranscriptError::Malformed(number));
+                };
+                let bytes =
+                    |field| hex::decode(field).map_err(|_| TranscriptError::Malformed(number));
+                let rpc = Rpc::from_name(rpc)
+                    .filter(|rpc| introduced_in(*rpc) <= version)
+                    .ok_or(TranscriptError::Malformed(number))?;
+                let credentials = match credentials {
+                    "-" => Vec::new(),
+                    keys => keys
+                        .split(',')
+                        .map(|key| CREDENTIALS.into_iter().find(|known| *known == key))
+                        .collect::<Option<_>>()
+                        .ok_or(TranscriptError::Malformed(number))?,
+                };
+                Ok(Exchange {
+                    rpc,
+                    credentials,
+                    request: bytes(request)?,
+                    response: bytes(response)?,
+                    payload: bytes(payload)?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { exchanges })
+    }
+
+    /// Sends every recorded request to `oracle` and checks it answers exactly
+    /// as recorded. Fails on exchanges that carried credentials.
+    pub async fn replay<O: DecryptionOracle>(&self, oracle: &O) -> Result<(), TranscriptError> {
+        self.replay_with(oracle, |_, _| None).await
+    }
+
+    /// Like [`replay`](Self::replay), but sends the value
+    /// `credentials(exchange, key)` for every credential an exchange carried.
+    pub async fn replay_with<O: DecryptionOracle>(
+        &self,
+        oracle: &O,
+        credentials: impl Fn(usize, &str) -> Option<String>,
+    ) -> Result<(), TranscriptError> {
+        for (index, exchange) in self.exchanges.iter().enumerate() {
+            let status = |status| TranscriptError::Status {
+                exchange: index,
+                status,
+            };
+            match exchange.rpc {
+                Rpc::Decrypt => {
+                    let (request, response) =
+                        exchange.decode::<DecryptRequest, DecryptResponse>(index)?;
+                    let payload = decrypt_response_payload(&request, &response);
+                    let call = exchange.request(index, request.clone(), &credentials)?;
+                    let replayed = oracle.decrypt(call).await.map_err(status)?;
+                    exchange.check(index, (&request, &response), payload, replayed.get_ref())?;
+                }
+                Rpc::Reencrypt => {
+                    let (request, response) =
+                        exchange.decode::<ReencryptRequest, ReencryptResponse>(index)?;
+                    let payload = reencrypt_response_payload(&request, &response);
+                    let call = exchange.request(index, request.clone(), &credentials)?;
+                    let replayed = oracle.reencrypt(call).await.map_err(status)?;
+                    exchange.check(index, (&request, &response), payload, replayed.get_ref())?;
+                }
+                Rpc::AssertIsNil => {
+                    let (request, response) =
+                        exchange.decode::<IsNilRequest, IsNilResponse>(index)?;
+                    let payload = is_nil_response_payload(&request, &response);
+                    let call = exchange.request(index, request.clone(), &credentials)?;
+                    let replayed = oracle.assert_is_nil(call).await.map_err(status)?;
+                    exchange.check(index, (&request, &response), payload, replayed.get_ref())?;
+                }
+                Rpc::Aggregate => {
+                    let (request, response) =
+                        exchange.decode::<AggregateRequest, AggregateResponse>(index)?;
+                    let payload = aggregate_response_payload(&request, &response);
+                    let call = exchange.request(index, request.clone(), &credentials)?;
+                    let replayed = oracle.aggregate(call).await.map_err(status)?;
+                    exchange.check(index, (&request, &response), payload, replayed.get_ref())?;
+                }
+                Rpc::Bucket => {
+                    let (request, response) =
+                        exchange.decode::<BucketRequest, BucketResponse>(index)?;
+                    let payload = bucket_response_payload(&request, &response);
+                    let call = exchange.request(index, request.clone(), &credentials)?;
+                    let replayed = oracle.bucket(call).await.map_err(status)?;
+                    exchange.check(index, (&request, &response), payload, replayed.get_ref())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An oracle that records the exchanges it serves.
+pub struct Recorder<O> {
+    inner: O,
+    transcript: Arc<Mutex<Transcript>>,
+}
+
+impl<O: DecryptionOracle> Recorder<O> {
+    pub fn new(inner: O) -> Self {
+        Self {
+            inner,
+            transcript: Arc::default(),
+        }
+    }
+
+    /// Everything recorded so far.
+    pub fn transcript(&self) -> Transcript {
+        self.transcript.lock().unwrap().clone()
+    }
+
+    fn record(&self, exchange: Exchange) {
+        self.transcript.lock().unwrap().exchanges.push(exchange);
+    }
+}
+
+#[tonic::async_trait]
+impl<O: DecryptionOracle> DecryptionOracle for Recorder<O> {
+    async fn decrypt(
+        &self,
+        request: Request<DecryptRequest>,
+    ) -> Result<Response<DecryptResponse>, Status> {
+        let metadata = request.metadata().clone();
+        let message = request.get_ref().clone();
+        let response = self.inner.decrypt(request).await?;
+        let payload = decrypt_response_payload(&message, response.get_ref());
+        self.record(Exchange::new(
+            Rpc::Decrypt,
+            &metadata,
+            &message,
+            response.get_ref(),
+            payload,
+        ));
+        Ok(response)
+    }
+
+    async fn reencrypt(
+        &self,
+        request: Request<ReencryptRequest>,
+    ) -> Result<Response<ReencryptResponse>, Status> {
+        let metadata = request.metadata().clone();
+        let message = request.get_ref().clone();
+        let response = self.inner.reencrypt(request).await?;
+        let payload = reencrypt_response_payload(&message, response.get_ref());
+        self.record(Exchange::new(
+            Rpc::Reencrypt,
+            &metadata,
+            &message,
+            response.get_ref(),
+            payload,
+        ));
+        Ok(response)
+    }
+
+    async fn assert_is_nil(
+        &self,
+        request: Request<IsNilRequest>,
+    ) -> Result<Response<IsNilResponse>, Status> {
+        let metadata = request.metadata().clone();
+        let message = request.get_ref().clone();
+        let response = self.inner.assert_is_nil(request).await?;
+        let payload = is_nil_response_payload(&message, response.get_ref());
+        self.record(Exchange::new(
+            Rpc::AssertIsNil,
+            &metadata,
+            &message,
+            response.get_ref(),
+            payload,
+        ));
+        Ok(response)
+    }
+
+    async fn aggregate(
+        &self,
+        request: Request<AggregateRequest>,
+    ) -> Result<Response<AggregateResponse>, Status> {
+        let metadata = request.metadata().clone();
+        let message = request.get_ref().clone();
+        let response = self.inner.aggregate(request).await?;
+        let payload = aggregate_response_payload(&message, response.get_ref());
+        self.record(Exchange::new(
+            Rpc::Aggregate,
+            &metadata,
+            &message,
+            response.get_ref(),
+            payload,
+        ));
+        Ok(response)
+    }
+
+    async fn bucket(
+        &self,
+        request: Request<BucketRequest>,
+    ) -> Result<Response<BucketResponse>, Status> {
+        let metadata = request.metadata().clone();
+        let message = request.get_ref().clone();
+        let response = self.inner.bucket(request).await?;
+        let payload = bucket_response_payload(&message, response.get_ref());
+        self.record(Exchange::new(
+            Rpc::Bucket,
+            &metadata,
+            &message,
+            response.get_ref(),
+            payload,
+        ));
+        Ok(response)
+    }
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "server")]
+
+use decryption_oracle_proto::buckets::{BucketSet, BucketSets};
+use tonic::Code;
+
+#[test]
+fn bucket_sets_need_ascending_bounds() {
+    assert!(BucketSet::new(Vec::new()).is_none());
+    assert!(BucketSet::new(vec![10, 10]).is_none());
+    assert!(BucketSet::new(vec![20, 10]).is_none());
+
+    let set = BucketSet::new(vec![10, 100]).unwrap();
+    assert_eq!(set.bucket(9), 0);
+    assert_eq!(set.bucket(10), 1);
+    assert_eq!(set.bucket(500), 2);
+}
+
+#[test]
+fn bucket_sets_are_scoped_to_a_key() {
+    let sets = BucketSets::new();
+    sets.set("key-a", "tiers", BucketSet::new(vec![10]).unwrap());
+
+    assert_eq!(sets.get("key-a", "tiers").unwrap().bucket(10), 1);
+    let err = sets.get("key-b", "tiers").unwrap_err();
+    assert_eq!(err.code(), Code::NotFound);
+
+    assert!(sets.remove("key-a", "tiers").is_some());
+    assert!(sets.get("key-a", "tiers").is_err());
+}
@@ -0,0 +1,113 @@
+#![cfg(all(feature = "chaos", feature = "client"))]
+
+use std::time::{Duration, Instant};
+
+use decryption_oracle_proto::chaos::{Chaos, Faults, InvalidRate, KeyOutage};
+use decryption_oracle_proto::local::{LocalOracle, MockKeys};
+use decryption_oracle_proto::oracle::EncryptedType;
+use decryption_oracle_proto::{DecryptRequest, DecryptionOracle};
+use tonic::{Code, Request};
+
+fn oracle() -> LocalOracle<MockKeys, fn(&[u8]) -> String> {
+    LocalOracle::new(MockKeys, |_| String::new())
+}
+
+fn decrypt() -> Request<DecryptRequest> {
+    let encrypted = MockKeys::encrypt(7, EncryptedType::Uint8).unwrap();
+    Request::new(DecryptRequest::new(encrypted))
+}
+
+#[test]
+fn rates_outside_the_unit_interval_are_rejected() {
+    for rate in [f64::NAN, -0.1, 1.5] {
+        let faults = Faults {
+            hang_rate: rate,
+            ..Faults::default()
+        };
+        let Err(err) = Chaos::new(oracle(), faults) else {
+            panic!("accepted hang_rate {rate}");
+        };
+        assert_eq!(err.field, "hang_rate");
+    }
+    let faults = Faults {
+        corrupt_rate: 2.0,
+        ..Faults::default()
+    };
+    assert!(matches!(
+        Chaos::new(oracle(), faults),
+        Err(InvalidRate {
+            field: "corrupt_rate",
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn faults_fail_or_stall_calls() {
+    let clean = Chaos::new(oracle(), Faults::default()).unwrap();
+    let response = clean.decrypt(decrypt()).await.unwrap();
+    assert_eq!(response.get_ref().decrypted, "7");
+
+    let dropping = Faults {
+        drop_rate: 1.0,
+        ..Faults::default()
+    };
+    let dropping = Chaos::new(oracle(), dropping).unwrap();
+    let err = dropping.decrypt(decrypt()).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unavailable);
+
+    let outage = Faults {
+        key_outage: Some(KeyOutage {
+            every: Duration::from_secs(3600),
+            lasting: Duration::from_secs(3600),
+        }),
+        ..Faults::default()
+    };
+    let outage = Chaos::new(oracle(), outage).unwrap();
+    let err = outage.decrypt(decrypt()).await.unwrap_err();
+    assert_eq!(err.code(), Code::Unavailable);
+
+    let hanging = Faults {
+        hang_rate: 1.0,
+        ..Faults::default()
+    };
+    let hanging = Chaos::new(oracle(), hanging).unwrap();
+    let deadline = Duration::from_millis(50);
+    let call = tokio::time::timeout(deadline, hanging.decrypt(decrypt()));
+    assert!(call.await.is_err());
+}
+
+#[tokio::test]
+async fn corrupted_ciphertexts_decrypt_differently() {
+    let corrupting = Faults {
+        corrupt_rate: 1.0,
+        ..Faults::default()
+    };
+    let corrupting = Chaos::new(oracle(), corrupting).unwrap();
+    for _ in 0..8 {
+        let response = corrupting.decrypt(decrypt()).await.unwrap();
+        let decrypted: u8 = response.get_ref().decrypted.parse().unwrap();
+        assert_eq!((decrypted ^ 7).count_ones(), 1, "got {decrypted}");
+    }
+}
+
+#[tokio::test]
+async fn latency_stays_within_the_bound() {
+    let max_latency = Duration::from_millis(20);
+    let slow = Faults {
+        max_latency,
+        ..Faults::default()
+    };
+    let slow = Chaos::new(oracle(), slow).unwrap();
+    let mut slowest = Duration::ZERO;
+    for _ in 0..10 {
+        let started = Instant::now();
+        slow.decrypt(decrypt()).await.unwrap();
+        slowest = slowest.max(started.elapsed());
+    }
+    assert!(slowest > Duration::from_millis(1));
+    assert!(
+        slowest < max_latency + Duration::from_millis(50),
+        "took {slowest:?}"
+    );
+}
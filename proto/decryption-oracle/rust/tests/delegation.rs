@@ -0,0 +1,126 @@
+use decryption_oracle_proto::delegation::{Caveat, DelegationError, DelegationToken};
+use decryption_oracle_proto::oracle::{EncryptedType, FheEncrypted};
+use decryption_oracle_proto::ReencryptRequest;
+
+const ROOT_KEY: &[u8] = b"owner root key";
+const NOW: u64 = 1_700_000_000;
+
+fn encrypted() -> FheEncrypted {
+    FheEncrypted::new(vec![7, 0], EncryptedType::Uint16)
+}
+
+fn request(encrypted: FheEncrypted, user_key: &str) -> ReencryptRequest {
+    ReencryptRequest::builder()
+        .encrypted(encrypted)
+        .user_key(user_key)
+        .build()
+        .unwrap()
+}
+
+fn owner(identifier: &str, _: &FheEncrypted) -> bool {
+    identifier == "alice"
+}
+
+fn scoped() -> DelegationToken {
+    DelegationToken::issue(ROOT_KEY, "alice").attenuate(Caveat::ciphertext(&encrypted()))
+}
+
+fn verify(token: &DelegationToken, request: &ReencryptRequest) -> Result<(), DelegationError> {
+    token.verify_reencrypt(ROOT_KEY, request, NOW, owner)
+}
+
+#[test]
+fn scoped_token_verifies_and_round_trips() {
+    let token = scoped()
+        .attenuate(Caveat::Recipient("04ab".into()))
+        .attenuate(Caveat::ExpiresAt(NOW));
+    assert_eq!(verify(&token, &request(encrypted(), "04ab")), Ok(()));
+
+    assert_eq!(
+        DelegationToken::from_bytes(&token.to_bytes()),
+        Ok(token.clone())
+    );
+    assert_eq!(
+        DelegationToken::from_hex(&token.to_hex()),
+        Ok(token.clone())
+    );
+
+    let mut trailing = token.to_bytes();
+    trailing.push(0);
+    assert_eq!(
+        DelegationToken::from_bytes(&trailing),
+        Err(DelegationError::Malformed)
+    );
+}
+
+#[test]
+fn token_must_name_a_ciphertext_its_issuer_owns() {
+    let unscoped = DelegationToken::issue(ROOT_KEY, "alice");
+    assert_eq!(
+        verify(&unscoped, &request(encrypted(), "04ab")),
+        Err(DelegationError::Unscoped)
+    );
+
+    let other = FheEncrypted::new(vec![8, 0], EncryptedType::Uint16);
+    assert_eq!(
+        verify(&scoped(), &request(other, "04ab")),
+        Err(DelegationError::CiphertextMismatch)
+    );
+
+    let retyped = FheEncrypted::new(vec![7, 0], EncryptedType::Uint8);
+    assert_eq!(
+        verify(&scoped(), &request(retyped, "04ab")),
+        Err(DelegationError::CiphertextMismatch)
+    );
+
+    let mallory =
+        DelegationToken::issue(ROOT_KEY, "mallory").attenuate(Caveat::ciphertext(&encrypted()));
+    assert_eq!(
+        verify(&mallory, &request(encrypted(), "04ab")),
+        Err(DelegationError::NotOwner)
+    );
+}
+
+#[test]
+fn caveats_cannot_be_removed() {
+    let narrowed = scoped().attenuate(Caveat::Recipient("04ab".into()));
+    let narrowed_bytes = narrowed.to_bytes();
+    let mut stripped = scoped().to_bytes();
+    let tag_at = stripped.len() - 32;
+    stripped[tag_at..].copy_from_slice(&narrowed_bytes[narrowed_bytes.len() - 32..]);
+
+    let stripped = DelegationToken::from_bytes(&stripped).unwrap();
+    assert_eq!(
+        verify(&stripped, &request(encrypted(), "04cd")),
+        Err(DelegationError::BadTag)
+    );
+}
+
+#[test]
+fn tag_binds_the_root_key() {
+    let mut tampered = scoped().to_bytes();
+    *tampered.last_mut().unwrap() ^= 1;
+    let tampered = DelegationToken::from_bytes(&tampered).unwrap();
+    assert_eq!(
+        verify(&tampered, &request(encrypted(), "04ab")),
+        Err(DelegationError::BadTag)
+    );
+
+    let result = scoped().verify_reencrypt(b"other key", &request(encrypted(), "04ab"), NOW, owner);
+    assert_eq!(result, Err(DelegationError::BadTag));
+}
+
+#[test]
+fn caveats_restrict_recipient_and_time() {
+    let token = scoped().attenuate(Caveat::Recipient("04ab".into()));
+    assert_eq!(
+        verify(&token, &request(encrypted(), "04cd")),
+        Err(DelegationError::RecipientMismatch)
+    );
+
+    let token = scoped().attenuate(Caveat::ExpiresAt(NOW - 1));
+    assert_eq!(
+        verify(&token, &request(encrypted(), "04ab")),
+        Err(DelegationError::Expired)
+    );
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "server")]
+
+use decryption_oracle_proto::lineage::{handle, LineageRegistry};
+use decryption_oracle_proto::oracle::{EncryptedType, FheEncrypted};
+use tonic::Code;
+
+fn encrypted(byte: u8) -> FheEncrypted {
+    FheEncrypted::new(vec![byte], EncryptedType::Uint8)
+}
+
+fn id(byte: u8) -> [u8; 32] {
+    handle(&encrypted(byte).data)
+}
+
+#[test]
+fn derived_values_charge_their_inputs() {
+    let registry = LineageRegistry::new();
+    registry.set_budget(id(1), 2);
+    registry.set_budget(id(2), 5);
+    registry.record(id(10), &[id(1), id(2)]).unwrap();
+    registry.record(id(11), &[id(10), id(1)]).unwrap();
+
+    registry.charge(&[encrypted(11)]).unwrap();
+    assert_eq!(registry.remaining(id(1)), Some(1));
+    assert_eq!(registry.remaining(id(2)), Some(4));
+
+    registry.charge(&[encrypted(1)]).unwrap();
+    let err = registry.charge(&[encrypted(10)]).unwrap_err();
+    assert_eq!(err.code(), Code::ResourceExhausted);
+}
+
+#[test]
+fn refused_charges_deduct_nothing() {
+    let registry = LineageRegistry::new();
+    registry.set_budget(id(1), 0);
+    registry.set_budget(id(2), 3);
+
+    let err = registry.charge(&[encrypted(2), encrypted(1)]).unwrap_err();
+    assert_eq!(err.code(), Code::ResourceExhausted);
+    let err = registry.charge(&[encrypted(2), encrypted(9)]).unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    assert_eq!(registry.remaining(id(2)), Some(3));
+}
+
+#[test]
+fn unknown_handles_are_refused() {
+    let registry = LineageRegistry::new();
+    assert_eq!(registry.remaining(id(1)), None);
+    let err = registry.charge(&[encrypted(1)]).unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    let err = registry.record(id(10), &[id(1)]).unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+    let err = registry.record(id(10), &[]).unwrap_err();
+    assert_eq!(err.code(), Code::InvalidArgument);
+    let err = registry.charge(&[encrypted(10)]).unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+
+    registry.set_budget(id(1), 1);
+    registry.record(id(10), &[id(1)]).unwrap();
+    registry.forget(id(10));
+    let err = registry.charge(&[encrypted(10)]).unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
@@ -0,0 +1,176 @@
+#![cfg(all(feature = "client", feature = "server"))]
+// Interceptors report failures as `tonic::Status`, like the handlers.
+#![allow(clippy::result_large_err)]
+
+use std::sync::Arc;
+
+use decryption_oracle_proto::buckets::BucketSet;
+use decryption_oracle_proto::client::{ClientError, OracleClient};
+use decryption_oracle_proto::delegation::{Caveat, DelegationToken};
+use decryption_oracle_proto::lineage::{handle, LineageRegistry};
+use decryption_oracle_proto::local::{LocalOracle, MockKeys, LOCAL_KEY_ID};
+use decryption_oracle_proto::oracle::{AggregateOp, EncryptedType};
+use decryption_oracle_proto::protocol::{NoProof, Protocol, ProtocolError};
+use decryption_oracle_proto::rpc::Rpc;
+use decryption_oracle_proto::tenant::{ApiKeys, TenantConfig, TenantRegistry};
+use decryption_oracle_proto::{DecryptionOracleClient, DecryptionOracleServer};
+use sha2::{Digest, Sha256};
+use tonic::service::interceptor::InterceptedService;
+use tonic::{Code, Request};
+
+fn sign(payload: &[u8]) -> String {
+    hex::encode(Sha256::digest(payload))
+}
+
+fn verify(payload: &[u8], signature: &str) -> bool {
+    sign(payload) == signature
+}
+
+#[tokio::test]
+async fn local_client_round_trips() {
+    let tiers = BucketSet::new(vec![100, 1000]).unwrap();
+    let oracle = LocalOracle::new(MockKeys, sign).with_bucket_set("tiers", tiers);
+    let mut client = OracleClient::local(oracle, Protocol::new(NoProof, verify));
+
+    let value = MockKeys::encrypt(42, EncryptedType::Uint64).unwrap();
+    assert_eq!(client.decrypt(value.clone()).await.unwrap(), "42");
+    assert_eq!(
+        client.reencrypt(value.clone(), "04ab").await.unwrap(),
+        "2a00000000000000"
+    );
+    assert!(!client.is_nil(value).await.unwrap());
+    assert!(client
+        .is_nil(MockKeys::encrypt(0, EncryptedType::Uint256).unwrap())
+        .await
+        .unwrap());
+
+    let values = [3, 10, 20].map(|v| MockKeys::encrypt(v, EncryptedType::Uint8).unwrap());
+    let sum = client.aggregate(values.to_vec(), AggregateOp::Sum, "");
+    assert_eq!(sum.await.unwrap(), "33");
+    let above = client.aggregate(values.to_vec(), AggregateOp::CountAbove, "5");
+    assert_eq!(above.await.unwrap(), "2");
+
+    let value = MockKeys::encrypt(150, EncryptedType::Uint16).unwrap();
+    assert_eq!(client.bucket(value.clone(), "tiers").await.unwrap(), 1);
+    let err = client.bucket(value, "fine").await.unwrap_err();
+    let ClientError::Status(status) = err else {
+        panic!("expected a status, got {err:?}");
+    };
+    assert_eq!(status.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn local_aggregates_wrap_sums_but_not_means() {
+    let oracle = LocalOracle::new(MockKeys, sign).with_min_aggregate_size(3);
+    let mut client = OracleClient::local(oracle, Protocol::new(NoProof, verify));
+
+    let values = [200, 100, 2].map(|v| MockKeys::encrypt(v, EncryptedType::Uint8).unwrap());
+    let sum = client.aggregate(values.to_vec(), AggregateOp::Sum, "");
+    assert_eq!(sum.await.unwrap(), "46");
+    let mean = client.aggregate(values.to_vec(), AggregateOp::Mean, "");
+    assert_eq!(mean.await.unwrap(), "100");
+
+    let err = client
+        .aggregate(values[..2].to_vec(), AggregateOp::Sum, "")
+        .await
+        .unwrap_err();
+    let ClientError::Status(status) = err else {
+        panic!("expected a status, got {err:?}");
+    };
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+async fn local_aggregates_charge_every_input() {
+    let lineage = Arc::new(LineageRegistry::new());
+    let target = MockKeys::encrypt(42, EncryptedType::Uint64).unwrap();
+    let zero = MockKeys::encrypt(0, EncryptedType::Uint64).unwrap();
+    lineage.set_budget(handle(&target.data), 1);
+    lineage.set_budget(handle(&zero.data), 5);
+    let oracle = LocalOracle::new(MockKeys, sign).with_lineage(lineage.clone());
+    let mut client = OracleClient::local(oracle, Protocol::new(NoProof, verify));
+
+    let values = vec![target.clone(), zero.clone()];
+    let sum = client.aggregate(values.clone(), AggregateOp::Sum, "");
+    assert_eq!(sum.await.unwrap(), "42");
+    assert_eq!(lineage.remaining(handle(&zero.data)), Some(4));
+
+    for err in [
+        client
+            .aggregate(values, AggregateOp::CountAbove, "41")
+            .await,
+        client.decrypt(target).await,
+    ] {
+        let ClientError::Status(status) = err.unwrap_err() else {
+            panic!("expected a status");
+        };
+        assert_eq!(status.code(), Code::ResourceExhausted);
+    }
+    assert_eq!(lineage.remaining(handle(&zero.data)), Some(4));
+}
+
+#[tokio::test]
+async fn local_reencrypt_checks_delegation_tokens() {
+    let oracle = LocalOracle::new(MockKeys, sign).with_delegation(b"root".to_vec(), "alice");
+    let mut client = OracleClient::local(oracle, Protocol::new(NoProof, verify));
+    let value = MockKeys::encrypt(42, EncryptedType::Uint64).unwrap();
+
+    let err = client.reencrypt(value.clone(), "04ab").await.unwrap_err();
+    let ClientError::Status(status) = err else {
+        panic!("expected a status, got {err:?}");
+    };
+    assert_eq!(status.code(), Code::PermissionDenied);
+
+    let token = DelegationToken::issue(b"root", "alice").attenuate(Caveat::ciphertext(&value));
+    let reencrypted = client.reencrypt_delegated(value, "04ab", &token).await;
+    assert_eq!(reencrypted.unwrap(), "2a00000000000000");
+}
+
+#[tokio::test]
+async fn local_client_sends_its_api_key() {
+    let registry = TenantRegistry::new(
+        ApiKeys::new().with("secret-a", "a"),
+        [("a".to_owned(), TenantConfig::new(LOCAL_KEY_ID))],
+    );
+    let admit = move |request: Request<()>| {
+        registry.admit(&request, Rpc::Decrypt)?;
+        Ok(request)
+    };
+    let server = DecryptionOracleServer::new(LocalOracle::new(MockKeys, sign));
+    let inner = DecryptionOracleClient::new(InterceptedService::new(server, admit));
+    let mut client = OracleClient::new(inner, Protocol::new(NoProof, verify));
+    let value = MockKeys::encrypt(42, EncryptedType::Uint64).unwrap();
+
+    let err = client.decrypt(value.clone()).await.unwrap_err();
+    let ClientError::Status(status) = err else {
+        panic!("expected a status, got {err:?}");
+    };
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    let mut client = client.with_api_key("secret-a").unwrap();
+    assert_eq!(client.decrypt(value).await.unwrap(), "42");
+}
+
+#[test]
+fn mock_keys_refuse_values_that_do_not_fit() {
+    let err = MockKeys::encrypt(300, EncryptedType::Uint8).unwrap_err();
+    assert_eq!(err.code(), Code::OutOfRange);
+    let max = MockKeys::encrypt(u128::from(u16::MAX), EncryptedType::Uint16).unwrap();
+    assert_eq!(max.data, [0xff, 0xff]);
+    assert!(MockKeys::encrypt(u128::MAX, EncryptedType::Uint128).is_ok());
+}
+
+#[tokio::test]
+async fn local_client_checks_signatures() {
+    let oracle = LocalOracle::new(MockKeys, |_: &[u8]| String::from("forged"));
+    let mut client = OracleClient::local(oracle, Protocol::new(NoProof, verify));
+
+    let err = client
+        .decrypt(MockKeys::encrypt(1, EncryptedType::Uint8).unwrap())
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ClientError::Protocol(ProtocolError::BadSignature)
+    ));
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "server")]
+
+use std::sync::Arc;
+
+use decryption_oracle_proto::oracle::EncryptedType;
+use decryption_oracle_proto::postprocess::{
+    Bucketize, Chain, DiscreteLaplace, PostProcessor, PostProcessors, PrivacyBudget,
+    PrivacyBudgets, RoundDown,
+};
+use decryption_oracle_proto::tenant::{Admission, TenantConfig};
+use tonic::Code;
+
+fn admission(tenant: &str, key_id: &str) -> Admission {
+    Admission {
+        tenant: tenant.to_owned(),
+        config: Arc::new(TenantConfig::new(key_id)),
+        fee: 0,
+    }
+}
+
+#[test]
+fn round_down_to_the_step() {
+    assert!(RoundDown::new(0).is_none());
+    let round = RoundDown::new(100).unwrap();
+    assert_eq!(
+        round.process("key-a", EncryptedType::Uint32, 1299).unwrap(),
+        1200
+    );
+    assert_eq!(
+        round.process("key-a", EncryptedType::Uint32, 99).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn bucketize_releases_lower_bounds() {
+    let buckets = Bucketize::new(vec![1000, 10, 100, 10]);
+    let ty = EncryptedType::Uint64;
+    assert_eq!(buckets.process("key-a", ty, 9).unwrap(), 0);
+    assert_eq!(buckets.process("key-a", ty, 10).unwrap(), 10);
+    assert_eq!(buckets.process("key-a", ty, 999).unwrap(), 100);
+    assert_eq!(buckets.process("key-a", ty, 5000).unwrap(), 1000);
+}
+
+#[test]
+fn chain_applies_processors_in_order() {
+    let ty = EncryptedType::Uint256;
+    let wide = "1".repeat(60);
+    assert_eq!(Chain::new().apply("key-a", ty, &wide).unwrap(), wide);
+
+    let chain = Chain::new()
+        .then(Bucketize::new(vec![0, 250]))
+        .then(RoundDown::new(100).unwrap());
+    assert_eq!(chain.apply("key-a", ty, "260").unwrap(), "200");
+    assert_eq!(
+        chain.apply("key-a", ty, &wide).unwrap_err().code(),
+        Code::OutOfRange
+    );
+}
+
+#[test]
+fn tenant_chains_run_after_key_chains() {
+    let processors = PostProcessors::new(Chain::new().then(RoundDown::new(1000).unwrap()));
+    processors.set_key("key-a", Chain::new().then(RoundDown::new(100).unwrap()));
+    processors.set_tenant("t1", Chain::new().then(RoundDown::new(10).unwrap()));
+
+    let ty = EncryptedType::Uint32;
+    let apply = |tenant, key_id| {
+        processors
+            .apply(&admission(tenant, key_id), ty, "1234")
+            .unwrap()
+    };
+    assert_eq!(apply("t1", "key-a"), "1200");
+    assert_eq!(apply("t1", "key-b"), "1000");
+    assert_eq!(apply("t2", "key-a"), "1200");
+
+    processors.set_tenant("t1", Chain::new().then(RoundDown::new(500).unwrap()));
+    assert_eq!(apply("t1", "key-a"), "1000");
+    assert!(processors.remove_tenant("t1").is_some());
+    assert_eq!(apply("t1", "key-a"), "1200");
+}
+
+#[test]
+fn privacy_budgets_reject_invalid_epsilon() {
+    for epsilon in [f64::NAN, f64::INFINITY, -1.0] {
+        assert!(PrivacyBudget::new(epsilon).is_none());
+    }
+    assert_eq!(PrivacyBudget::new(0.5).unwrap().remaining(), 0.5);
+
+    let budgets = Arc::new(PrivacyBudgets::new());
+    assert!(DiscreteLaplace::new(0, 1, 1, budgets.clone()).is_none());
+    assert!(DiscreteLaplace::new(1, 0, 1, budgets.clone()).is_none());
+    assert!(DiscreteLaplace::new(1, 1, 0, budgets).is_none());
+}
+
+#[test]
+fn noise_is_charged_to_the_key_budget() {
+    let budgets = Arc::new(PrivacyBudgets::new());
+    budgets.set("key-a", PrivacyBudget::new(1.0).unwrap());
+    let processors = PostProcessors::new(
+        Chain::new().then(DiscreteLaplace::new(1, 2, 1, budgets.clone()).unwrap()),
+    );
+    let ty = EncryptedType::Uint8;
+
+    assert!(processors
+        .apply(&admission("t1", "key-a"), ty, "10")
+        .is_ok());
+    assert!(processors
+        .apply(&admission("t2", "key-a"), ty, "10")
+        .is_ok());
+    assert_eq!(budgets.remaining("key-a"), Some(0.0));
+    let err = processors
+        .apply(&admission("t3", "key-a"), ty, "10")
+        .unwrap_err();
+    assert_eq!(err.code(), Code::ResourceExhausted);
+
+    let err = processors
+        .apply(&admission("t1", "key-b"), ty, "10")
+        .unwrap_err();
+    assert_eq!(err.code(), Code::FailedPrecondition);
+}
+
+#[test]
+fn noise_follows_the_discrete_laplace_distribution() {
+    let budgets = Arc::new(PrivacyBudgets::new());
+    budgets.set("key-a", PrivacyBudget::new(1e9).unwrap());
+    let laplace = DiscreteLaplace::new(1, 1, 1, budgets).unwrap();
+
+    const SAMPLES: usize = 20_000;
+    let noise: Vec<i128> = (0..SAMPLES)
+        .map(|_| {
+            let noisy = laplace
+                .process("key-a", EncryptedType::Uint32, 1000)
+                .unwrap();
+            noisy as i128 - 1000
+        })
+        .collect();
+
+    // P(0) = (1 - e^-1) / (1 + e^-1) and the mean is 0.
+    let alpha = (-1f64).exp();
+    let zeros = noise.iter().filter(|noise| **noise == 0).count();
+    let p_zero = zeros as f64 / SAMPLES as f64;
+    assert!(
+        (p_zero - (1.0 - alpha) / (1.0 + alpha)).abs() < 0.03,
+        "{p_zero}"
+    );
+    let mean = noise.iter().sum::<i128>() as f64 / SAMPLES as f64;
+    assert!(mean.abs() < 0.1, "{mean}");
+}
@@ -0,0 +1,70 @@
+#![cfg(feature = "server")]
+// Proof generators report failures as `tonic::Status`, like the handlers.
+#![allow(clippy::result_large_err)]
+
+use std::time::Duration;
+
+use decryption_oracle_proto::oracle::{EncryptedType, FheEncrypted};
+use decryption_oracle_proto::proofcache::{ProofCache, ProofKey};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+fn key(byte: u8) -> ProofKey {
+    ProofKey::decrypt(&FheEncrypted::new(vec![byte], EncryptedType::Uint8), 1)
+}
+
+/// The cost of one entry holding a 4 byte proof.
+fn entry_cost() -> usize {
+    let cache = ProofCache::new(HOUR, usize::MAX);
+    cache.insert(key(0), "0000".into());
+    cache.size()
+}
+
+#[test]
+fn expired_proofs_are_dropped() {
+    let cache = ProofCache::new(Duration::ZERO, usize::MAX);
+    cache.insert(key(1), "0001".into());
+    assert_eq!(cache.get(&key(1)), None);
+    assert_eq!(cache.size(), 0);
+
+    let cache = ProofCache::new(HOUR, usize::MAX);
+    cache.insert(key(1), "0001".into());
+    assert_eq!(cache.get(&key(1)).as_deref(), Some("0001"));
+    let other_epoch = ProofKey::decrypt(&FheEncrypted::new(vec![1], EncryptedType::Uint8), 2);
+    assert_eq!(cache.get(&other_epoch), None);
+}
+
+#[test]
+fn over_budget_evicts_oldest_first() {
+    let cache = ProofCache::new(HOUR, 2 * entry_cost());
+    cache.insert(key(1), "0001".into());
+    cache.insert(key(2), "0002".into());
+    cache.insert(key(1), "0011".into());
+    cache.insert(key(3), "0003".into());
+
+    assert_eq!(cache.get(&key(2)), None);
+    assert_eq!(cache.get(&key(1)).as_deref(), Some("0011"));
+    assert_eq!(cache.get(&key(3)).as_deref(), Some("0003"));
+    assert_eq!(cache.size(), 2 * entry_cost());
+
+    let tiny = ProofCache::new(HOUR, entry_cost() - 1);
+    tiny.insert(key(1), "0001".into());
+    assert_eq!(tiny.size(), 0);
+}
+
+#[test]
+fn replacing_a_proof_does_not_grow_the_cache() {
+    let cache = ProofCache::new(HOUR, usize::MAX);
+    cache.insert(key(1), "0002".into());
+    for _ in 0..1000 {
+        cache.insert(key(1), "0001".into());
+    }
+    assert_eq!(cache.size(), entry_cost());
+    assert_eq!(cache.get(&key(1)).as_deref(), Some("0001"));
+
+    let generated = cache.get_or_generate(key(1), || panic!("cached proof regenerated"));
+    assert_eq!(generated.unwrap(), "0001");
+    let generated = cache.get_or_generate(key(2), || Ok("0002".into()));
+    assert_eq!(generated.unwrap(), "0002");
+    assert_eq!(cache.size(), 2 * entry_cost());
+}
@@ -0,0 +1,105 @@
+use decryption_oracle_proto::oracle::{AggregateRequest, EncryptedType, FheEncrypted};
+use decryption_oracle_proto::request::{RequestError, MIN_AGGREGATE_SIZE};
+use decryption_oracle_proto::{BucketRequest, DecryptRequest, IsNilRequest, ReencryptRequest};
+
+fn value(byte: u8) -> FheEncrypted {
+    FheEncrypted::new(vec![byte], EncryptedType::Uint8)
+}
+
+#[test]
+fn single_ciphertext_requests_need_a_known_nonempty_value() {
+    assert_eq!(DecryptRequest::for_uint8(vec![1]).validate(), Ok(()));
+    assert_eq!(
+        DecryptRequest::default().validate(),
+        Err(RequestError::MissingEncrypted)
+    );
+    assert_eq!(
+        IsNilRequest::for_uint64(Vec::new()).validate(),
+        Err(RequestError::EmptyCiphertext)
+    );
+
+    let mut request = DecryptRequest::new(value(1));
+    request.encrypted.as_mut().unwrap().r#type = 42;
+    assert_eq!(request.validate(), Err(RequestError::UnknownType(42)));
+}
+
+#[test]
+fn reencrypt_needs_a_user_key() {
+    let built = ReencryptRequest::builder().encrypted(value(1)).build();
+    assert_eq!(built, Err(RequestError::MissingUserKey));
+
+    let built = ReencryptRequest::builder().user_key("04ab").build();
+    assert_eq!(built, Err(RequestError::MissingEncrypted));
+
+    let built = ReencryptRequest::builder()
+        .encrypted(value(1))
+        .user_key("04ab")
+        .build();
+    assert!(built.is_ok());
+}
+
+#[test]
+fn aggregate_rejects_unknown_ops() {
+    let mut request = AggregateRequest::sum(vec![value(1), value(2)]);
+    assert_eq!(request.validate(), Ok(()));
+    request.op = 7;
+    assert_eq!(request.validate(), Err(RequestError::UnknownOp(7)));
+}
+
+#[test]
+fn aggregate_checks_values_and_threshold() {
+    assert_eq!(
+        AggregateRequest::sum(Vec::new()).validate(),
+        Err(RequestError::MissingEncrypted)
+    );
+    assert_eq!(
+        AggregateRequest::mean(vec![value(1), FheEncrypted::default()]).validate(),
+        Err(RequestError::EmptyCiphertext)
+    );
+
+    let values = vec![value(1), value(2)];
+    assert_eq!(
+        AggregateRequest::count_above(values.clone(), "10").validate(),
+        Ok(())
+    );
+    for threshold in ["", "-1", "1.5", "ten"] {
+        assert_eq!(
+            AggregateRequest::count_above(values.clone(), threshold).validate(),
+            Err(RequestError::BadThreshold)
+        );
+    }
+}
+
+#[test]
+fn aggregate_needs_distinct_values_of_one_type() {
+    assert_eq!(
+        AggregateRequest::sum(vec![value(1)]).validate(),
+        Err(RequestError::TooFewValues(MIN_AGGREGATE_SIZE))
+    );
+    assert_eq!(
+        AggregateRequest::sum(vec![value(1), value(1)]).validate(),
+        Err(RequestError::DuplicateCiphertext)
+    );
+    let wide = FheEncrypted::new(vec![2, 0], EncryptedType::Uint16);
+    assert_eq!(
+        AggregateRequest::mean(vec![value(1), wide]).validate(),
+        Err(RequestError::MixedTypes)
+    );
+
+    let values = vec![value(1), value(2)];
+    let request = AggregateRequest::sum(values.clone());
+    assert_eq!(request.validate_min_size(0), Ok(()));
+    assert_eq!(
+        request.validate_min_size(3),
+        Err(RequestError::TooFewValues(3))
+    );
+}
+
+#[test]
+fn bucket_names_a_set() {
+    assert_eq!(BucketRequest::new(value(1), "tiers").validate(), Ok(()));
+    assert_eq!(
+        BucketRequest::new(value(1), "").validate(),
+        Err(RequestError::MissingBucketSet)
+    );
+}
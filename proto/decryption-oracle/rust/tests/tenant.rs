@@ -0,0 +1,150 @@
+#![cfg(feature = "server")]
+
+use std::collections::HashSet;
+
+use decryption_oracle_proto::metadata::API_KEY;
+use decryption_oracle_proto::rpc::Rpc;
+use decryption_oracle_proto::tenant::{
+    ApiKeys, FeeSchedule, RateLimit, TenantConfig, TenantRegistry,
+};
+use tonic::{Code, Request};
+
+fn request(api_key: Option<&str>) -> Request<()> {
+    let mut request = Request::new(());
+    if let Some(api_key) = api_key {
+        request
+            .metadata_mut()
+            .insert(API_KEY, api_key.parse().unwrap());
+    }
+    request
+}
+
+fn throttled(burst: u32) -> TenantConfig {
+    TenantConfig {
+        rate_limit: RateLimit::new(0.0, burst),
+        ..TenantConfig::new("key-a")
+    }
+}
+
+fn keys() -> ApiKeys {
+    ApiKeys::new().with("secret-a", "a")
+}
+
+fn registry(config: TenantConfig) -> TenantRegistry {
+    TenantRegistry::new(keys(), [("a".to_owned(), config)])
+}
+
+#[test]
+fn admit_authenticates_the_caller() {
+    let registry = registry(TenantConfig::new("key-a"));
+
+    let admission = registry.admit(&request(Some("secret-a")), Rpc::Decrypt);
+    assert_eq!(admission.unwrap().tenant, "a");
+
+    let err = registry.admit(&request(None), Rpc::Decrypt).unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+    let err = registry
+        .admit(&request(Some("guess")), Rpc::Decrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+
+    let mut claimed = request(None);
+    claimed
+        .metadata_mut()
+        .insert("x-tenant-id", "a".parse().unwrap());
+    let err = registry.admit(&claimed, Rpc::Decrypt).unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[test]
+fn admit_enforces_allowed_rpcs_and_fees() {
+    let config = TenantConfig {
+        allowed_rpcs: HashSet::from([Rpc::Decrypt]),
+        fees: FeeSchedule::flat(3).with(Rpc::Decrypt, 5),
+        ..TenantConfig::new("key-a")
+    };
+    let registry = registry(config);
+
+    let admission = registry.admit(&request(Some("secret-a")), Rpc::Decrypt);
+    assert_eq!(admission.unwrap().fee, 5);
+    let err = registry
+        .admit(&request(Some("secret-a")), Rpc::Reencrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+}
+
+#[test]
+fn admit_throttles_at_the_burst() {
+    let registry = registry(throttled(2));
+    for _ in 0..2 {
+        assert!(registry
+            .admit(&request(Some("secret-a")), Rpc::Decrypt)
+            .is_ok());
+    }
+    let err = registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::ResourceExhausted);
+}
+
+#[test]
+fn reload_keeps_the_bucket_unless_the_limit_changes() {
+    let registry = registry(throttled(1));
+    assert!(registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .is_ok());
+
+    let config = TenantConfig {
+        fees: FeeSchedule::flat(1),
+        ..throttled(1)
+    };
+    registry.reload(keys(), [("a".to_owned(), config)]);
+    assert_eq!(registry.get("a").unwrap().fees, FeeSchedule::flat(1));
+    let err = registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::ResourceExhausted);
+
+    registry.reload(keys(), [("a".to_owned(), throttled(2))]);
+    assert!(registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .is_ok());
+
+    registry.reload(keys(), []);
+    let err = registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::PermissionDenied);
+}
+
+#[test]
+fn reload_rotates_keys_and_admits_new_tenants() {
+    let registry = registry(TenantConfig::new("key-a"));
+    let keys = ApiKeys::new().with("secret-a2", "a").with("secret-b", "b");
+    registry.reload(
+        keys,
+        [
+            ("a".to_owned(), TenantConfig::new("key-a")),
+            ("b".to_owned(), TenantConfig::new("key-b")),
+        ],
+    );
+
+    let admission = registry.admit(&request(Some("secret-b")), Rpc::Decrypt);
+    assert_eq!(admission.unwrap().config.key_id, "key-b");
+    let admission = registry.admit(&request(Some("secret-a2")), Rpc::Decrypt);
+    assert_eq!(admission.unwrap().tenant, "a");
+    let err = registry
+        .admit(&request(Some("secret-a")), Rpc::Decrypt)
+        .unwrap_err();
+    assert_eq!(err.code(), Code::Unauthenticated);
+}
+
+#[test]
+fn rate_limits_must_be_finite_and_non_negative() {
+    for per_second in [f64::NAN, f64::INFINITY, -1.0] {
+        assert!(RateLimit::new(per_second, 1).is_none());
+    }
+    assert!(RateLimit::new(1.0, 0).is_none());
+    let limit = RateLimit::new(0.5, 3).unwrap();
+    assert_eq!((limit.per_second(), limit.burst()), (0.5, 3));
+}
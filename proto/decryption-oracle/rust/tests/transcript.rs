@@ -0,0 +1,256 @@
+#![cfg(feature = "server")]
+
+use std::path::Path;
+
+use decryption_oracle_proto::buckets::BucketSet;
+use decryption_oracle_proto::metadata::{delegation_token, DELEGATION_TOKEN};
+use decryption_oracle_proto::oracle::{AggregateOp, EncryptedType, FheEncrypted};
+use decryption_oracle_proto::protocol::{
+    aggregate_response_payload, bucket_response_payload, decrypt_response_payload,
+    is_nil_response_payload, reencrypt_response_payload,
+};
+use decryption_oracle_proto::transcript::{Recorder, Transcript, TranscriptError};
+use decryption_oracle_proto::{
+    AggregateRequest, AggregateResponse, BucketRequest, BucketResponse, DecryptRequest,
+    DecryptResponse, DecryptionOracle, IsNilRequest, IsNilResponse, ReencryptRequest,
+    ReencryptResponse,
+};
+use sha2::{Digest, Sha256};
+use tonic::{Request, Response, Status};
+
+const GOLDEN: &str = "tests/golden/transcript-v2.txt";
+
+const TOKEN: &str = "0123abcd";
+
+fn credentials(_exchange: usize, key: &str) -> Option<String> {
+    (key == DELEGATION_TOKEN).then(|| TOKEN.to_owned())
+}
+
+/// Deterministic stand-in for a real oracle: "decrypts" by summing the
+/// ciphertext bytes and signs with the SHA-256 of the signing payload.
+struct TestOracle {
+    offset: u64,
+}
+
+fn sign(payload: &[u8]) -> String {
+    hex::encode(Sha256::digest(payload))
+}
+
+fn plaintext(encrypted: &Option<FheEncrypted>, offset: u64) -> u64 {
+    let data = encrypted.as_ref().map_or(&[][..], |e| &e.data);
+    data.iter().map(|b| u64::from(*b)).sum::<u64>() + offset
+}
+
+#[tonic::async_trait]
+impl DecryptionOracle for TestOracle {
+    async fn decrypt(
+        &self,
+        request: Request<DecryptRequest>,
+    ) -> Result<Response<DecryptResponse>, Status> {
+        let request = request.into_inner();
+        let mut response = DecryptResponse {
+            decrypted: plaintext(&request.encrypted, self.offset).to_string(),
+            signature: String::new(),
+        };
+        response.signature = sign(&decrypt_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn reencrypt(
+        &self,
+        request: Request<ReencryptRequest>,
+    ) -> Result<Response<ReencryptResponse>, Status> {
+        if delegation_token(&request).is_none() {
+            return Err(Status::permission_denied("missing delegation token"));
+        }
+        let request = request.into_inner();
+        let value = plaintext(&request.encrypted, self.offset);
+        let mut response = ReencryptResponse {
+            reencrypted: format!("{}:{value}", request.user_public_key),
+            signature: String::new(),
+        };
+        response.signature = sign(&reencrypt_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn assert_is_nil(
+        &self,
+        request: Request<IsNilRequest>,
+    ) -> Result<Response<IsNilResponse>, Status> {
+        let request = request.into_inner();
+        let mut response = IsNilResponse {
+            is_nil: plaintext(&request.encrypted, self.offset) == 0,
+            signature: String::new(),
+        };
+        response.signature = sign(&is_nil_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn aggregate(
+        &self,
+        request: Request<AggregateRequest>,
+    ) -> Result<Response<AggregateResponse>, Status> {
+        let request = request.into_inner();
+        let values = request
+            .encrypted
+            .iter()
+            .map(|e| plaintext(&Some(e.clone()), self.offset));
+        let aggregate = match request.op() {
+            AggregateOp::Sum => values.sum::<u64>(),
+            AggregateOp::Mean => values.sum::<u64>() / request.encrypted.len() as u64,
+            AggregateOp::CountAbove => {
+                let threshold: u64 = request.threshold.parse().unwrap();
+                values.filter(|v| *v > threshold).count() as u64
+            }
+        };
+        let mut response = AggregateResponse {
+            aggregate: aggregate.to_string(),
+            signature: String::new(),
+        };
+        response.signature = sign(&aggregate_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+
+    async fn bucket(
+        &self,
+        request: Request<BucketRequest>,
+    ) -> Result<Response<BucketResponse>, Status> {
+        let request = request.into_inner();
+        let value = plaintext(&request.encrypted, self.offset);
+        let tiers = BucketSet::new(vec![100, 1000]).unwrap();
+        assert_eq!(request.bucket_set, "tiers");
+        let mut response = BucketResponse {
+            bucket: tiers.bucket(value.into()),
+            signature: String::new(),
+        };
+        response.signature = sign(&bucket_response_payload(&request, &response));
+        Ok(Response::new(response))
+    }
+}
+
+async fn record() -> Transcript {
+    let oracle = Recorder::new(TestOracle { offset: 0 });
+    let values = vec![
+        FheEncrypted::new(vec![1, 2, 3], EncryptedType::Uint8),
+        FheEncrypted::new(vec![40, 2], EncryptedType::Uint8),
+    ];
+
+    oracle
+        .decrypt(Request::new(DecryptRequest::for_uint64(vec![7, 7, 7])))
+        .await
+        .unwrap();
+    let request = ReencryptRequest::builder()
+        .encrypted(FheEncrypted::new(vec![9; 4], EncryptedType::Uint32))
+        .user_key("04ab")
+        .proof("p")
+        .build()
+        .unwrap();
+    let mut request = Request::new(request);
+    request
+        .metadata_mut()
+        .insert(DELEGATION_TOKEN, TOKEN.parse().unwrap());
+    oracle.reencrypt(request).await.unwrap();
+    oracle
+        .assert_is_nil(Request::new(IsNilRequest::for_uint256(vec![0; 32])))
+        .await
+        .unwrap();
+    oracle
+        .aggregate(Request::new(AggregateRequest::sum(values.clone())))
+        .await
+        .unwrap();
+    oracle
+        .aggregate(Request::new(AggregateRequest::count_above(values, "10")))
+        .await
+        .unwrap();
+    let request = BucketRequest::new(
+        FheEncrypted::new(vec![100, 50], EncryptedType::Uint16),
+        "tiers",
+    );
+    oracle.bucket(Request::new(request)).await.unwrap();
+
+    oracle.transcript()
+}
+
+/// Run with `UPDATE_GOLDEN=1` to rewrite the golden file after an intended
+/// wire or signing change, and bump the transcript version alongside it.
+#[tokio::test]
+async fn recording_matches_golden() {
+    let text = record().await.to_text();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(GOLDEN);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, &text).unwrap();
+    }
+    assert_eq!(text, std::fs::read_to_string(path).unwrap());
+}
+
+#[tokio::test]
+async fn golden_replays() {
+    let transcript = Transcript::parse(include_str!("golden/transcript-v2.txt")).unwrap();
+    assert_eq!(transcript.exchanges.len(), 6);
+    let oracle = TestOracle { offset: 0 };
+    transcript.replay_with(&oracle, credentials).await.unwrap();
+}
+
+#[tokio::test]
+async fn version_1_golden_still_replays() {
+    let transcript = Transcript::parse(include_str!("golden/transcript-v1.txt")).unwrap();
+    assert_eq!(transcript.exchanges.len(), 5);
+    let oracle = TestOracle { offset: 0 };
+    transcript.replay_with(&oracle, credentials).await.unwrap();
+}
+
+#[tokio::test]
+async fn credentials_are_recorded_by_key_only() {
+    let transcript = record().await;
+    assert_eq!(transcript.exchanges[1].credentials, [DELEGATION_TOKEN]);
+    assert!(transcript.exchanges[0].credentials.is_empty());
+    assert!(!transcript.to_text().contains(TOKEN));
+
+    let err = transcript
+        .replay(&TestOracle { offset: 0 })
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TranscriptError::MissingCredential {
+            exchange: 1,
+            key: DELEGATION_TOKEN
+        }
+    ));
+}
+
+#[tokio::test]
+async fn replay_catches_changed_responses() {
+    let transcript = Transcript::parse(include_str!("golden/transcript-v2.txt")).unwrap();
+    let err = transcript
+        .replay(&TestOracle { offset: 1 })
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        TranscriptError::Mismatch {
+            exchange: 0,
+            what: "response"
+        }
+    ));
+}
+
+#[test]
+fn parse_rejects_other_versions() {
+    for version in ["0", "3", "x"] {
+        let err = Transcript::parse(&format!("oracle-transcript {version}\n")).unwrap_err();
+        assert!(matches!(err, TranscriptError::UnsupportedVersion(v) if v == version));
+    }
+}
+
+#[test]
+fn version_1_cannot_hold_bucket_exchanges() {
+    let bucket = include_str!("golden/transcript-v2.txt")
+        .lines()
+        .find(|line| line.starts_with("Bucket "))
+        .unwrap();
+    let text = format!("oracle-transcript 1\n{bucket}\n");
+    let err = Transcript::parse(&text).unwrap_err();
+    assert!(matches!(err, TranscriptError::Malformed(2)));
+    assert!(Transcript::parse(&text.replace(" 1\n", " 2\n")).is_ok());
+}